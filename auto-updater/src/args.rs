@@ -2,14 +2,11 @@ use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use clap::Parser;
-
-use crate::uart_params::UartParams;
+use ezsp_fwupd::{UartParams, XmodemArgs, XmodemConfig};
 
 const DEFAULT_MANIFEST: &str = "/etc/ezsp-firmware-update.json";
 const DEFAULT_TIMEOUT: u64 = 1000; // Milliseconds
 const DEFAULT_REBOOT_GRACE_TIME: u64 = 4000; // Milliseconds
-const DEFAULT_CHANNEL_SIZE: usize = 8;
-const PROTOCOL_VERSION: u8 = 8;
 const MAX_RETRIES: u8 = 5;
 
 /// Command line arguments for the firmware update tool.
@@ -23,14 +20,32 @@ pub struct Args {
     timeout: u64,
     #[clap(long, short = 'r', help = "grace time to wait for the device to reboot", default_value_t = DEFAULT_REBOOT_GRACE_TIME)]
     reboot_grace_time: u64,
-    #[clap(long, short = 'C', help = "callback channel size", default_value_t = DEFAULT_CHANNEL_SIZE)]
+    #[clap(long, short = 'C', help = "callback channel size", default_value_t = UartParams::default().callback_channel_size())]
     callback_channel_size: usize,
-    #[clap(long, short = 'R', help = "response channel size", default_value_t = DEFAULT_CHANNEL_SIZE)]
+    #[clap(long, short = 'R', help = "response channel size", default_value_t = UartParams::default().response_channel_size())]
     response_channel_size: usize,
-    #[clap(long, short = 'p', help = "EZSP protocol version to use", default_value_t = PROTOCOL_VERSION)]
+    #[clap(long, short = 'p', help = "EZSP protocol version to use", default_value_t = UartParams::default().protocol_version())]
     protocol_version: u8,
     #[clap(long, short = 'm', help = "maximum amount of retries on repeatable fallible operations", default_value_t = MAX_RETRIES)]
     max_retries: u8,
+    #[clap(
+        long,
+        help = "verify the firmware's signature against the given public key file"
+    )]
+    verify_signature: Option<PathBuf>,
+    #[clap(
+        long,
+        help = "a data file with additional manufacturer and image type names"
+    )]
+    registry: Option<PathBuf>,
+    #[clap(
+        long,
+        help = "update the firmware even if the image does not support the device's hardware version or the device reports none, \
+                the hardware version being read from the CustomVersion manufacturing token"
+    )]
+    ignore_hardware_version: bool,
+    #[clap(flatten)]
+    xmodem: XmodemArgs,
 }
 
 impl Args {
@@ -73,4 +88,28 @@ impl Args {
     pub const fn max_retries(&self) -> u8 {
         self.max_retries
     }
+
+    /// Return the public key file to verify the firmware's signature against, if any.
+    #[must_use]
+    pub fn verify_signature(&self) -> Option<&Path> {
+        self.verify_signature.as_deref()
+    }
+
+    /// Return the data file with additional manufacturer and image type names, if any.
+    #[must_use]
+    pub fn registry(&self) -> Option<&Path> {
+        self.registry.as_deref()
+    }
+
+    /// Return whether to skip the hardware version compatibility check.
+    #[must_use]
+    pub const fn ignore_hardware_version(&self) -> bool {
+        self.ignore_hardware_version
+    }
+
+    /// Return the XMODEM configuration to use for the firmware transfer.
+    #[must_use]
+    pub const fn xmodem_config(&self) -> XmodemConfig {
+        self.xmodem.config()
+    }
 }
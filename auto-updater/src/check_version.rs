@@ -0,0 +1,56 @@
+use std::cmp::Ordering;
+
+use log::{error, info, warn};
+use semver::Version;
+
+/// Check that the version stored in the firmware image matches the manifest version.
+///
+/// Build metadata is ignored in the comparison.
+/// Returns `true` if the versions match or if the image does not carry a version.
+pub fn check_version(firmware_version: Option<Version>, version: &Version) -> bool {
+    let Some(firmware_version) = firmware_version else {
+        warn!("Firmware image does not contain a version. Trusting manifest version {version}.");
+        return true;
+    };
+
+    info!("Firmware version: {firmware_version}");
+
+    if firmware_version.cmp_precedence(version) == Ordering::Equal {
+        return true;
+    }
+
+    error!("Manifest version {version} does not match firmware image version {firmware_version}.");
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use ezsp_fwupd::Firmware;
+    use semver::Version;
+
+    use super::check_version;
+
+    /// A GBL image with the `EmberZNet` application version 7.4.3.
+    const GBL: [u8; 64] = [
+        0xEB, 0x17, 0xA6, 0x03, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00,
+        0x00, 0xF4, 0x0A, 0x0A, 0xF4, 0x1C, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x03,
+        0x04, 0x07, 0x00, 0x00, 0x00, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09,
+        0x0A, 0x0B, 0x0C, 0x0D, 0x0E, 0x0F, 0x10, 0xFC, 0x04, 0x04, 0xFC, 0x04, 0x00, 0x00, 0x00,
+        0xA2, 0x92, 0xB4, 0xC4,
+    ];
+
+    #[test]
+    fn test_check_version() {
+        let version = Version::new(7, 4, 3);
+        assert!(check_version(None, &version));
+        assert!(check_version(Some(Version::new(7, 4, 3)), &version));
+        assert!(!check_version(Some(Version::new(7, 4, 2)), &version));
+    }
+
+    #[test]
+    fn test_gbl_version_mismatch() {
+        let firmware = Firmware::try_from(GBL.to_vec()).unwrap();
+        assert!(check_version(firmware.version(), &Version::new(7, 4, 3)));
+        assert!(!check_version(firmware.version(), &Version::new(7, 4, 1)));
+    }
+}
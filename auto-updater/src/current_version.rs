@@ -5,14 +5,12 @@ use ashv2::TryCloneNative;
 use ezsp::GetValueExt;
 use ezsp::ezsp::value::EmberVersion;
 use ezsp::uart::Uart;
-use ezsp_fwupd::make_uart;
+use ezsp_fwupd::{UartParams, make_uart};
 use log::{debug, error};
 use semver::Version;
 use serialport::SerialPort;
 use tokio::time::sleep;
 
-use crate::uart_params::UartParams;
-
 /// Extension trait for getting the current firmware version from a Zigbee device.
 pub trait CurrentVersion {
     /// Await the current firmware version from the Zigbee device.
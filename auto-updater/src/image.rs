@@ -0,0 +1,68 @@
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+
+use ezsp_fwupd::ota_file::OtaReader;
+use ezsp_fwupd::{Firmware, Preflight};
+use semver::Version;
+
+/// A firmware image that is either loaded into memory or streamed from an OTA file.
+#[derive(Debug)]
+pub enum Image {
+    /// A firmware image loaded into memory.
+    Loaded(Firmware),
+    /// An OTA file whose upgrade image is streamed from disk.
+    Streamed(OtaReader<BufReader<File>>),
+}
+
+impl Image {
+    /// Return the firmware loaded into memory, if any.
+    #[must_use]
+    pub const fn firmware(&self) -> Option<&Firmware> {
+        match self {
+            Self::Loaded(firmware) => Some(firmware),
+            Self::Streamed(_) => None,
+        }
+    }
+
+    /// Return the firmware version stored in the image, if present.
+    #[must_use]
+    pub fn version(&self) -> Option<Version> {
+        match self {
+            Self::Loaded(firmware) => firmware.version(),
+            Self::Streamed(ota_reader) => {
+                Some(ota_reader.header().decoded_firmware_version().semver())
+            }
+        }
+    }
+
+    /// Return the requirements that the device must meet before the image may be flashed.
+    #[must_use]
+    pub fn preflight(&self) -> Preflight {
+        match self {
+            Self::Loaded(firmware) => Preflight::from_firmware(firmware),
+            Self::Streamed(ota_reader) => Preflight::from_ota_reader(ota_reader),
+        }
+    }
+
+    /// Return a reader over the payload that is to be sent to the bootloader.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`io::Error`] if the image contains no payload or reading it fails.
+    pub fn payload(&mut self) -> io::Result<Box<dyn Read + Send + '_>> {
+        let payload: Option<Box<dyn Read + Send + '_>> = match self {
+            Self::Loaded(firmware) => firmware
+                .payload()
+                .map(|payload| -> Box<dyn Read + Send> { Box::new(payload) }),
+            Self::Streamed(ota_reader) => ota_reader
+                .upgrade_image()?
+                .map(|payload| -> Box<dyn Read + Send + '_> { Box::new(payload) }),
+        };
+        payload.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "OTA file contains no upgrade image",
+            )
+        })
+    }
+}
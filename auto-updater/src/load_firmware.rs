@@ -0,0 +1,60 @@
+use std::fs::{File, read};
+use std::io::BufReader;
+
+use ezsp_fwupd::Firmware;
+use ezsp_fwupd::ota_file::{OtaError, OtaReader, Registry};
+use log::{error, info};
+
+use crate::image::Image;
+use crate::manifest::Metadata;
+
+/// Extension trait to load and validate the firmware from the metadata.
+pub trait LoadFirmware {
+    /// Load and validate the firmware by reading it and checking its contents.
+    ///
+    /// If `stream` is `true`, OTA files are not loaded into memory, but streamed from disk and verified in chunks.
+    /// Manufacturer and image type names are resolved through the given registry.
+    fn load_firmware(&self, registry: &Registry, stream: bool) -> Option<Image>;
+}
+
+impl LoadFirmware for Metadata {
+    fn load_firmware(&self, registry: &Registry, stream: bool) -> Option<Image> {
+        if stream {
+            let file = File::open(self.filename())
+                .inspect_err(|error| error!("Failed to open firmware file: {error}"))
+                .ok()?;
+
+            match OtaReader::new(BufReader::new(file)) {
+                Ok(mut ota_reader) => {
+                    ota_reader
+                        .verify()
+                        .inspect_err(|error| error!("Failed to load firmware: {error}"))
+                        .ok()?;
+                    ota_reader.header().log_with(registry);
+                    return Some(Image::Streamed(ota_reader));
+                }
+                Err(OtaError::InvalidMagic(_)) => {}
+                Err(error) => {
+                    error!("Failed to load firmware: {error}");
+                    return None;
+                }
+            }
+        }
+
+        let firmware_bytes = read(self.filename())
+            .inspect_err(|error| error!("Failed to read firmware file: {error}"))
+            .ok()?;
+
+        let firmware = Firmware::try_from(firmware_bytes)
+            .inspect_err(|error| error!("Failed to load firmware: {error}"))
+            .ok()?;
+
+        match &firmware {
+            Firmware::Ota(ota_file) => ota_file.header().log_with(registry),
+            Firmware::Gbl(gbl) => info!("GBL image size:    {}", gbl.as_bytes().len()),
+            Firmware::Ebl(ebl) => info!("EBL image size:    {}", ebl.as_bytes().len()),
+        }
+
+        Some(Image::Loaded(firmware))
+    }
+}
@@ -1,26 +1,31 @@
 //! A firmware auto updater for Zigbee devices using the `ezsp` protocol.
 
+use std::path::Path;
 use std::process::ExitCode;
 
 use ashv2::{BaudRate, open};
 use clap::Parser;
-use log::{error, info};
+use ezsp_fwupd::PublicKey;
+use ezsp_fwupd::ota_file::Registry;
+use log::{error, info, warn};
 use serialport::FlowControl;
 
 use self::args::Args;
+use self::check_version::check_version;
 use self::current_version::get_current_version;
 use self::direction::Direction;
-use self::load_ota_file::LoadOtaFile;
+use self::load_firmware::LoadFirmware;
 use self::manifest::get_metadata;
 use self::update_firmware::update_firmware;
 use crate::validate_firmware::validate_firmware;
 
 mod args;
+mod check_version;
 mod current_version;
 mod direction;
-mod load_ota_file;
+mod image;
+mod load_firmware;
 mod manifest;
-mod uart_params;
 mod update_firmware;
 mod validate_firmware;
 
@@ -42,10 +47,30 @@ async fn main() -> ExitCode {
         }
     };
 
-    let Some(ota_file) = metadata.load_ota_file() else {
+    // Signature verification requires the entire image in memory.
+    let Some(mut image) = metadata.load_firmware(
+        &load_registry(args.registry()),
+        args.verify_signature().is_none(),
+    ) else {
         return ExitCode::FAILURE;
     };
 
+    if !check_version(image.version(), metadata.version()) {
+        return ExitCode::FAILURE;
+    }
+
+    if let Some(public_key) = args.verify_signature()
+        && !image.firmware().is_some_and(|firmware| {
+            PublicKey::from_file(public_key)
+                .and_then(|public_key| firmware.verify_signature(&public_key))
+                .inspect(|()| info!("Firmware signature verified."))
+                .inspect_err(|error| error!("Failed to verify firmware signature: {error}"))
+                .is_ok()
+        })
+    {
+        return ExitCode::FAILURE;
+    }
+
     let Ok(serial_port) = open(
         args.tty().to_string(),
         BaudRate::RstCts,
@@ -74,7 +99,10 @@ async fn main() -> ExitCode {
 
     match update_firmware(
         serial_port,
-        &ota_file,
+        &mut image,
+        &args.uart_params(),
+        !args.ignore_hardware_version(),
+        &args.xmodem_config(),
         direction,
         args.timeout(),
         args.reboot_grace_time(),
@@ -100,3 +128,18 @@ async fn main() -> ExitCode {
         }
     }
 }
+
+/// Load the built-in registry, extended by the given data file, if any.
+///
+/// Falls back to the built-in registry if the data file cannot be loaded.
+fn load_registry(path: Option<&Path>) -> Registry {
+    let mut registry = Registry::builtin().clone();
+
+    if let Some(path) = path
+        && let Err(error) = registry.extend_from_file(path)
+    {
+        warn!("Failed to load registry file '{}': {error}", path.display());
+    }
+
+    registry
+}
@@ -2,17 +2,27 @@ use std::io;
 use std::time::Duration;
 
 use ashv2::TryCloneNative;
-use ezsp_fwupd::{Fwupd, OtaFile};
+use ezsp_fwupd::{Fwupd, UartParams, XmodemConfig};
 use log::{error, info};
 use serialport::SerialPort;
 use tokio::time::sleep;
 
 use crate::direction::Direction;
+use crate::image::Image;
 
 /// Update the firmware of the Zigbee device.
+///
+/// The update is refused if the device does not meet the image's requirements.
+/// The requirements are checked over EZSP using the given UART parameters.
+/// If `check_hardware_version` is `false`, the device's hardware version is not checked.
+/// The firmware is sent according to the given XMODEM configuration.
+#[expect(clippy::too_many_arguments)]
 pub async fn update_firmware<T>(
     serial_port: T,
-    ota_file: &OtaFile,
+    image: &mut Image,
+    uart_params: &UartParams,
+    check_hardware_version: bool,
+    xmodem: &XmodemConfig,
     direction: Direction,
     timeout: Duration,
     reboot_grace_time: Duration,
@@ -20,9 +30,24 @@ pub async fn update_firmware<T>(
 where
     T: SerialPort + TryCloneNative + Send + Sync + 'static,
 {
+    let mut preflight = image.preflight();
+
+    if !check_hardware_version {
+        preflight = preflight.without_hardware_versions();
+    }
+
+    let payload = image.payload()?;
+
     info!("{} firmware...", direction.present_participle());
     let serial_port = serial_port
-        .fwupd(ota_file.payload().iter().copied(), Some(timeout), None)
+        .fwupd(
+            payload,
+            &preflight,
+            uart_params,
+            xmodem,
+            Some(timeout),
+            None,
+        )
         .await
         .inspect_err(|error| {
             error!("Firmware {direction} failed: {error}");
@@ -1,14 +1,13 @@
 use core::time::Duration;
 
 use ashv2::TryCloneNative;
-use ezsp_fwupd::{Reset, make_uart};
+use ezsp_fwupd::{Reset, UartParams, make_uart};
 use log::{error, info};
 use semver::Version;
 use serialport::SerialPort;
 
 use crate::current_version::CurrentVersion;
 use crate::direction::Direction;
-use crate::uart_params::UartParams;
 
 /// Validate the firmware version after the update.
 pub async fn validate_firmware<T>(
@@ -57,7 +56,7 @@ where
     );
 
     if new_version != *version {
-        error!("Firmware {direction} failed: expected version {version}, got {new_version}",);
+        error!("Firmware {direction} failed: expected version {version}, got {new_version}");
         return None;
     }
 
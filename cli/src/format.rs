@@ -0,0 +1,11 @@
+use clap::ValueEnum;
+
+/// Output formats of the CLI commands.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, ValueEnum)]
+pub enum Format {
+    /// Human-readable text.
+    #[default]
+    Text,
+    /// Machine-readable JSON.
+    Json,
+}
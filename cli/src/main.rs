@@ -1,6 +1,7 @@
 //! A firmware update utility for devices using the `ASHv2` and `XMODEM` protocols.
 
-use std::fs::read;
+use std::fs::{File, read};
+use std::io::{BufReader, Read};
 use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 use std::time::Duration;
@@ -8,13 +9,23 @@ use std::time::Duration;
 use ashv2::{BaudRate, open};
 use clap::{Parser, Subcommand};
 use ezsp::GetValueExt;
-use ezsp_fwupd::{FrameCount, Fwupd, OtaFile, Reset, make_uart};
+use ezsp_fwupd::ota_file::{OtaError, OtaReader};
+use ezsp_fwupd::{
+    Firmware, FrameCount, Fwupd, Preflight, PublicKey, Reset, UartParams, XmodemArgs, XmodemConfig,
+    make_uart,
+};
 use indicatif::{ProgressBar, ProgressStyle};
-use le_stream::FromLeStream;
 use log::error;
 use semver::Version;
+use serde_json::json;
 use serialport::FlowControl;
 
+use self::format::Format;
+use self::ota::OtaAction;
+
+mod format;
+mod ota;
+
 const DEFAULT_TIMEOUT: u64 = 1000; // Default timeout in milliseconds
 
 #[derive(Debug, Parser)]
@@ -29,10 +40,26 @@ enum Action {
     Flash {
         #[clap(index = 1, help = "the serial port to use for firmware update")]
         tty: String,
-        #[clap(index = 2, help = "the firmware file to upload")]
+        #[clap(
+            index = 2,
+            help = "the firmware file to upload, an OTA, GBL or EBL file"
+        )]
         firmware: PathBuf,
         #[clap(long, short, help = "serial port timeout in milliseconds", default_value_t = DEFAULT_TIMEOUT)]
         timeout: u64,
+        #[clap(
+            long,
+            help = "verify the firmware's signature against the given public key file"
+        )]
+        verify_signature: Option<PathBuf>,
+        #[clap(
+            long,
+            help = "flash the firmware even if the image does not support the device's hardware version or the device reports none, \
+                the hardware version being read from the CustomVersion manufacturing token"
+        )]
+        ignore_hardware_version: bool,
+        #[clap(flatten)]
+        xmodem: XmodemArgs,
     },
     #[clap(name = "reset", about = "Reset the device")]
     Reset {
@@ -45,13 +72,29 @@ enum Action {
     Query {
         #[clap(index = 1, help = "the serial port to use for firmware update")]
         tty: String,
+        #[clap(long, short, help = "the output format", value_enum, default_value_t = Format::Text)]
+        format: Format,
     },
-    #[clap(name = "ota", about = "Parse an OTA file")]
+    #[clap(
+        name = "ota",
+        about = "Parse, pack or unpack OTA files",
+        args_conflicts_with_subcommands = true,
+        subcommand_negates_reqs = true
+    )]
     Ota {
-        #[clap(index = 1, help = "the firmware file to upload")]
-        firmware: PathBuf,
+        #[clap(subcommand)]
+        action: Option<OtaAction>,
+        #[clap(index = 1, help = "the OTA file to parse", required = true)]
+        firmware: Option<PathBuf>,
         #[clap(long, short, help = "enable debug output")]
         debug: bool,
+        #[clap(
+            long,
+            help = "a data file with additional manufacturer and image type names"
+        )]
+        registry: Option<PathBuf>,
+        #[clap(long, short, help = "the output format", value_enum, default_value_t = Format::Text)]
+        format: Format,
     },
 }
 
@@ -65,25 +108,166 @@ async fn main() -> ExitCode {
             tty,
             ref firmware,
             timeout,
-        } => flash(tty, firmware, Duration::from_millis(timeout)).await,
+            ref verify_signature,
+            ignore_hardware_version,
+            ref xmodem,
+        } => {
+            flash(
+                tty,
+                firmware,
+                Duration::from_millis(timeout),
+                verify_signature.as_deref(),
+                !ignore_hardware_version,
+                &xmodem.config(),
+            )
+            .await
+        }
         Action::Reset { ref tty, timeout } => reset(tty, timeout.map(Duration::from_millis)),
-        Action::Query { ref tty } => query(tty).await,
+        Action::Query { ref tty, format } => query(tty, format).await,
         Action::Ota {
+            action,
             ref firmware,
             debug,
-        } => ota(firmware, debug),
+            ref registry,
+            format,
+        } => match (action, firmware) {
+            (Some(action), _) => action.run(),
+            (None, Some(firmware)) => ota::show(firmware, debug, registry.as_deref(), format),
+            (None, None) => {
+                error!("No OTA file specified");
+                ExitCode::FAILURE
+            }
+        },
     }
 }
 
 /// Flash the firmware onto the device.
-async fn flash(tty: String, firmware: &Path, timeout: Duration) -> ExitCode {
+///
+/// OTA files are streamed from disk and verified in chunks unless their signature is to be verified,
+/// which requires the entire file to be loaded into memory.
+///
+/// Flashing is refused if the device does not meet the image's requirements.
+/// If `check_hardware_version` is `false`, the device's hardware version is not checked.
+async fn flash(
+    tty: String,
+    firmware: &Path,
+    timeout: Duration,
+    public_key: Option<&Path>,
+    check_hardware_version: bool,
+    xmodem: &XmodemConfig,
+) -> ExitCode {
+    if public_key.is_none() {
+        match stream_ota(firmware) {
+            Ok(Some(mut ota_reader)) => {
+                if let Err(error) = ota_reader.verify() {
+                    error!("Failed to load firmware: {error}");
+                    return ExitCode::FAILURE;
+                }
+
+                let info = ota_reader.header().to_string();
+                let preflight = preflight(
+                    Preflight::from_ota_reader(&ota_reader),
+                    check_hardware_version,
+                );
+
+                let payload = match ota_reader.upgrade_image() {
+                    Ok(Some(payload)) => payload,
+                    Ok(None) => {
+                        error!("OTA file contains no upgrade image");
+                        return ExitCode::FAILURE;
+                    }
+                    Err(error) => {
+                        error!("Failed to read upgrade image: {error}");
+                        return ExitCode::FAILURE;
+                    }
+                };
+
+                let frame_count = payload.frame_count(xmodem.block_size());
+                return transmit(
+                    tty,
+                    payload,
+                    xmodem,
+                    frame_count,
+                    &preflight,
+                    &info,
+                    timeout,
+                )
+                .await;
+            }
+            Ok(None) => {}
+            Err(error) => {
+                error!("Failed to load firmware: {error}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
     let firmware: Vec<u8> = read(firmware).expect("Failed to read firmware file");
-    let ota_file = OtaFile::from_le_stream_exact(firmware.into_iter())
-        .expect("Failed to read ota file")
-        .validate()
-        .expect("Failed to validate ota file");
-    let firmware = ota_file.payload().to_vec();
-    let progress_bar = ProgressBar::new(firmware.frame_count() as u64);
+    let Ok(firmware) = Firmware::try_from(firmware)
+        .inspect_err(|error| error!("Failed to load firmware: {error}"))
+    else {
+        return ExitCode::FAILURE;
+    };
+
+    if let Some(public_key) = public_key
+        && let Err(error) = PublicKey::from_file(public_key)
+            .and_then(|public_key| firmware.verify_signature(&public_key))
+    {
+        error!("Failed to verify firmware signature: {error}");
+        return ExitCode::FAILURE;
+    }
+
+    let Some(payload) = firmware.payload() else {
+        error!("OTA file contains no upgrade image");
+        return ExitCode::FAILURE;
+    };
+
+    transmit(
+        tty,
+        payload,
+        xmodem,
+        payload.frame_count(xmodem.block_size()),
+        &preflight(Preflight::from_firmware(&firmware), check_hardware_version),
+        &firmware.to_string(),
+        timeout,
+    )
+    .await
+}
+
+/// Drop the hardware version requirement unless `check_hardware_version` is `true`.
+fn preflight(preflight: Preflight, check_hardware_version: bool) -> Preflight {
+    if check_hardware_version {
+        preflight
+    } else {
+        preflight.without_hardware_versions()
+    }
+}
+
+/// Open the given file as a streamed OTA file.
+///
+/// Returns `Ok(None)` if the file is not an OTA file.
+fn stream_ota(firmware: &Path) -> Result<Option<OtaReader<BufReader<File>>>, OtaError> {
+    match OtaReader::new(BufReader::new(File::open(firmware)?)) {
+        Ok(ota_reader) => Ok(Some(ota_reader)),
+        Err(OtaError::InvalidMagic(_)) => Ok(None),
+        Err(error) => Err(error),
+    }
+}
+
+/// Transmit the payload to the device, displaying the firmware info and a progress bar.
+async fn transmit<T>(
+    tty: String,
+    payload: T,
+    xmodem: &XmodemConfig,
+    frame_count: usize,
+    preflight: &Preflight,
+    info: &str,
+    timeout: Duration,
+) -> ExitCode
+where
+    T: Read,
+{
+    let progress_bar = ProgressBar::new(frame_count as u64);
     progress_bar.set_style(
         ProgressStyle::with_template(
             "[{elapsed_precise}] {bar:40.cyan/blue} {pos:>7}/{len:7} {msg}",
@@ -92,7 +276,7 @@ async fn flash(tty: String, firmware: &Path, timeout: Duration) -> ExitCode {
         .progress_chars("##-"),
     );
     progress_bar.println("### Firmware update info ###");
-    progress_bar.println(ota_file.to_string());
+    progress_bar.println(info);
 
     let Ok(serial_port) = open(tty.clone(), BaudRate::RstCts, FlowControl::Software)
         .inspect_err(|error| error!("Failed to open serial port '{tty}': {error}"))
@@ -101,7 +285,14 @@ async fn flash(tty: String, firmware: &Path, timeout: Duration) -> ExitCode {
     };
 
     let result = serial_port
-        .fwupd(firmware, Some(timeout), Some(&progress_bar))
+        .fwupd(
+            payload,
+            preflight,
+            &UartParams::default(),
+            xmodem,
+            Some(timeout),
+            Some(&progress_bar),
+        )
         .await
         .map(drop);
 
@@ -132,7 +323,7 @@ fn reset(tty: &str, timeout: Option<Duration>) -> ExitCode {
 }
 
 /// Query the device for version info.
-async fn query(tty: &str) -> ExitCode {
+async fn query(tty: &str, format: Format) -> ExitCode {
     let Ok(serial_port) = open(tty.to_string(), BaudRate::RstCts, FlowControl::Software)
         .inspect_err(|error| error!("Failed to open serial port '{tty}': {error}"))
     else {
@@ -154,10 +345,24 @@ async fn query(tty: &str) -> ExitCode {
     match version {
         Ok(result) => match result {
             Ok(version_info) => {
-                println!("{version_info}");
+                let description = version_info.to_string();
+                let semver = Version::try_from(version_info).ok();
+
+                match format {
+                    Format::Text => {
+                        println!("{description}");
 
-                if let Ok(semver) = Version::try_from(version_info) {
-                    println!("Semver: {semver}");
+                        if let Some(semver) = semver {
+                            println!("Semver: {semver}");
+                        }
+                    }
+                    Format::Json => println!(
+                        "{:#}",
+                        json!({
+                            "version": description,
+                            "semver": semver.map(|semver| semver.to_string()),
+                        })
+                    ),
                 }
 
                 ExitCode::SUCCESS
@@ -173,25 +378,3 @@ async fn query(tty: &str) -> ExitCode {
         }
     }
 }
-
-/// Parse an OTA file.
-fn ota(firmware: &Path, debug: bool) -> ExitCode {
-    let firmware: Vec<u8> = read(firmware).expect("Failed to read firmware file");
-    let Ok(ota_file) = OtaFile::from_le_stream_exact(firmware.into_iter()) else {
-        error!("Failed to read ota file");
-        return ExitCode::FAILURE;
-    };
-
-    let Ok(ota_file) = ota_file.validate() else {
-        error!("Failed to validate ota file");
-        return ExitCode::FAILURE;
-    };
-
-    if debug {
-        println!("Ota file:\n{ota_file:#04X?}");
-    } else {
-        println!("{ota_file}");
-    }
-
-    ExitCode::SUCCESS
-}
@@ -0,0 +1,301 @@
+use std::fs::{read, write};
+use std::num::ParseIntError;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::Subcommand;
+use ezsp_fwupd::ota_file::{OtaFileBuilder, Registry, Tag};
+use ezsp_fwupd::{Hex, OtaFile};
+use le_stream::ToLeStream;
+use log::{error, info};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::format::Format;
+
+mod diff;
+
+const ZIGBEE_PRO: u16 = 0x0002;
+
+/// Actions on OTA files.
+#[derive(Debug, Subcommand)]
+pub enum OtaAction {
+    #[clap(name = "pack", about = "Wrap a raw firmware image into an OTA file")]
+    Pack {
+        #[clap(index = 1, help = "the raw firmware image, e.g. a GBL file")]
+        image: PathBuf,
+        #[clap(index = 2, help = "the OTA file to write")]
+        output: PathBuf,
+        #[clap(long, short, help = "the manufacturer ID", value_parser = parse_int::<u16>)]
+        manufacturer_id: u16,
+        #[clap(long, short, help = "the image type", value_parser = parse_int::<u16>)]
+        image_type: u16,
+        #[clap(long, short, help = "the file version", value_parser = parse_int::<u32>)]
+        file_version: u32,
+        #[clap(long, short, help = "the Zigbee stack version", value_parser = parse_int::<u16>, default_value_t = ZIGBEE_PRO)]
+        stack_version: u16,
+        #[clap(long, short, help = "the header string", default_value = "")]
+        name: String,
+        #[clap(long, help = "the minimum hardware version", value_parser = parse_int::<u16>, requires = "max_hardware_version")]
+        min_hardware_version: Option<u16>,
+        #[clap(long, help = "the maximum hardware version", value_parser = parse_int::<u16>, requires = "min_hardware_version")]
+        max_hardware_version: Option<u16>,
+        #[clap(
+            long,
+            help = "a data file with additional manufacturer and image type names"
+        )]
+        registry: Option<PathBuf>,
+    },
+    #[clap(name = "diff", about = "Compare two OTA or GBL files")]
+    Diff {
+        #[clap(index = 1, help = "the old firmware file")]
+        old: PathBuf,
+        #[clap(index = 2, help = "the new firmware file")]
+        new: PathBuf,
+        #[clap(
+            long,
+            help = "a data file with additional manufacturer and image type names"
+        )]
+        registry: Option<PathBuf>,
+        #[clap(long, short, help = "the output format", value_enum, default_value_t = Format::Text)]
+        format: Format,
+    },
+    #[clap(name = "unpack", about = "Extract all sub-elements of an OTA file")]
+    Unpack {
+        #[clap(index = 1, help = "the OTA file to unpack")]
+        firmware: PathBuf,
+        #[clap(
+            long,
+            short,
+            help = "the directory to write the sub-elements to",
+            default_value = "."
+        )]
+        output_dir: PathBuf,
+    },
+}
+
+impl OtaAction {
+    /// Run the action.
+    pub fn run(self) -> ExitCode {
+        match self {
+            Self::Pack {
+                image,
+                output,
+                manufacturer_id,
+                image_type,
+                file_version,
+                stack_version,
+                name,
+                min_hardware_version,
+                max_hardware_version,
+                registry,
+            } => {
+                let Some(registry) = load_registry(registry.as_deref()) else {
+                    return ExitCode::FAILURE;
+                };
+
+                let mut builder = OtaFileBuilder::new(manufacturer_id, image_type, file_version)
+                    .zigbee_stack_version(stack_version)
+                    .name(name);
+
+                if let (Some(min), Some(max)) = (min_hardware_version, max_hardware_version) {
+                    builder = builder.hardware_versions(min..=max);
+                }
+
+                pack(&image, &output, builder, &registry)
+            }
+            Self::Diff {
+                old,
+                new,
+                registry,
+                format,
+            } => diff::diff(&old, &new, registry.as_deref(), format),
+            Self::Unpack {
+                firmware,
+                output_dir,
+            } => unpack(&firmware, &output_dir),
+        }
+    }
+}
+
+/// Parse and print an OTA file.
+pub fn show(firmware: &Path, debug: bool, registry: Option<&Path>, format: Format) -> ExitCode {
+    let Some(registry) = load_registry(registry) else {
+        return ExitCode::FAILURE;
+    };
+
+    let Some(ota_file) = load(firmware) else {
+        return ExitCode::FAILURE;
+    };
+
+    if format == Format::Json {
+        return print_json(&ota_file);
+    }
+
+    if debug {
+        println!("Ota file:\n{ota_file:#04X?}");
+    } else {
+        println!("{}", ota_file.describe(&registry));
+    }
+
+    ExitCode::SUCCESS
+}
+
+/// Print an OTA file as JSON, including the size and SHA-256 hash of its payload.
+fn print_json(ota_file: &OtaFile) -> ExitCode {
+    let report = OtaReport {
+        ota_file,
+        payload: ota_file.payload().map(|payload| Payload {
+            size: payload.len(),
+            sha256: sha256(payload),
+        }),
+    };
+
+    match serde_json::to_string_pretty(&report) {
+        Ok(json) => {
+            println!("{json}");
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            error!("Failed to serialize OTA file: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Return the SHA-256 hash of the given data as lower case hex digits.
+fn sha256(data: &[u8]) -> String {
+    format!("{:x}", Hex(&Sha256::digest(data)))
+}
+
+/// Wrap a raw firmware image into an OTA file.
+fn pack(image: &Path, output: &Path, builder: OtaFileBuilder, registry: &Registry) -> ExitCode {
+    let Ok(image) = read(image)
+        .inspect_err(|error| error!("Failed to read image file '{}': {error}", image.display()))
+    else {
+        return ExitCode::FAILURE;
+    };
+
+    let Some(tag) = Tag::new(Tag::UPGRADE_IMAGE, image) else {
+        error!("Image file is too large");
+        return ExitCode::FAILURE;
+    };
+
+    let Ok(ota_file) = builder
+        .tag(tag)
+        .build()
+        .inspect_err(|error| error!("Failed to build OTA file: {error}"))
+    else {
+        return ExitCode::FAILURE;
+    };
+
+    println!("{}", ota_file.describe(registry));
+
+    if let Err(error) = write(output, ota_file.to_le_stream().collect::<Vec<_>>()) {
+        error!("Failed to write OTA file '{}': {error}", output.display());
+        return ExitCode::FAILURE;
+    }
+
+    ExitCode::SUCCESS
+}
+
+/// Extract all sub-elements of an OTA file into separate files.
+fn unpack(firmware: &Path, output_dir: &Path) -> ExitCode {
+    let Some(ota_file) = load(firmware) else {
+        return ExitCode::FAILURE;
+    };
+
+    let stem = firmware
+        .file_stem()
+        .map_or_else(|| "ota".into(), |stem| stem.to_string_lossy());
+
+    for (index, tag) in ota_file.tags().iter().enumerate() {
+        let path = output_dir.join(format!("{stem}.{index}.{:04X}.bin", tag.id()));
+        info!("Writing tag {:#06X} to '{}'", tag.id(), path.display());
+
+        if let Err(error) = write(&path, tag.data()) {
+            error!("Failed to write '{}': {error}", path.display());
+            return ExitCode::FAILURE;
+        }
+    }
+
+    ExitCode::SUCCESS
+}
+
+/// Read, parse and validate an OTA file.
+fn load(firmware: &Path) -> Option<OtaFile> {
+    let firmware: Vec<u8> = read(firmware)
+        .inspect_err(|error| error!("Failed to read firmware file: {error}"))
+        .ok()?;
+
+    let ota_file = OtaFile::try_from(firmware.as_slice())
+        .inspect_err(|error| error!("Failed to read ota file: {error}"))
+        .ok()?;
+
+    ota_file
+        .validate()
+        .inspect_err(|error| error!("Failed to validate ota file: {error}"))
+        .ok()
+}
+
+/// Load the built-in registry, extended by the given data file, if any.
+fn load_registry(path: Option<&Path>) -> Option<Registry> {
+    let mut registry = Registry::builtin().clone();
+
+    if let Some(path) = path {
+        registry
+            .extend_from_file(path)
+            .inspect_err(|error| {
+                error!("Failed to load registry file '{}': {error}", path.display());
+            })
+            .ok()?;
+    }
+
+    Some(registry)
+}
+
+/// Parse an integer from a decimal or `0x`-prefixed hexadecimal string.
+fn parse_int<T>(value: &str) -> Result<T, ParseIntError>
+where
+    T: FromStrRadix,
+{
+    value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+        .map_or_else(
+            || T::from_str_radix(value, 10),
+            |hex| T::from_str_radix(hex, 16),
+        )
+}
+
+/// Integers that can be parsed from a string in a given radix.
+trait FromStrRadix: Sized {
+    fn from_str_radix(value: &str, radix: u32) -> Result<Self, ParseIntError>;
+}
+
+impl FromStrRadix for u16 {
+    fn from_str_radix(value: &str, radix: u32) -> Result<Self, ParseIntError> {
+        Self::from_str_radix(value, radix)
+    }
+}
+
+impl FromStrRadix for u32 {
+    fn from_str_radix(value: &str, radix: u32) -> Result<Self, ParseIntError> {
+        Self::from_str_radix(value, radix)
+    }
+}
+
+/// JSON representation of an OTA file.
+#[derive(Serialize)]
+struct OtaReport<'ota> {
+    #[serde(flatten)]
+    ota_file: &'ota OtaFile,
+    payload: Option<Payload>,
+}
+
+/// Summary of an OTA file's payload.
+#[derive(Serialize)]
+struct Payload {
+    size: usize,
+    sha256: String,
+}
@@ -0,0 +1,459 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::fs::read;
+use std::path::Path;
+use std::process::ExitCode;
+
+use ezsp_fwupd::gbl::{ApplicationInfo, Gbl};
+use ezsp_fwupd::ota_file::Registry;
+use ezsp_fwupd::{Firmware, Hex, OtaFile};
+use log::error;
+use serde::Serialize;
+
+use super::{load_registry, sha256};
+use crate::format::Format;
+
+/// Compare two firmware files and print their differences.
+pub fn diff(old: &Path, new: &Path, registry: Option<&Path>, format: Format) -> ExitCode {
+    let Some(registry) = load_registry(registry) else {
+        return ExitCode::FAILURE;
+    };
+
+    let (Some(old_firmware), Some(new_firmware)) = (load(old), load(new)) else {
+        return ExitCode::FAILURE;
+    };
+
+    let diff = Diff::new(&old_firmware, &new_firmware, &registry);
+
+    if format == Format::Json {
+        return match serde_json::to_string_pretty(&diff) {
+            Ok(json) => {
+                println!("{json}");
+                ExitCode::SUCCESS
+            }
+            Err(error) => {
+                error!("Failed to serialize diff: {error}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    println!("Old file:          {}", old.display());
+    println!("New file:          {}", new.display());
+    println!("{diff}");
+    ExitCode::SUCCESS
+}
+
+/// Read and parse a firmware file.
+fn load(path: &Path) -> Option<Firmware> {
+    let bytes = read(path)
+        .inspect_err(|error| error!("Failed to read '{}': {error}", path.display()))
+        .ok()?;
+
+    Firmware::try_from(bytes)
+        .inspect_err(|error| error!("Failed to load '{}': {error}", path.display()))
+        .ok()
+}
+
+/// The differences between two firmware files.
+#[derive(Debug, Serialize)]
+struct Diff {
+    direction: Direction,
+    application_matches: Option<bool>,
+    sections: Vec<Section>,
+}
+
+impl Diff {
+    /// Compare the old and the new firmware.
+    fn new(old: &Firmware, new: &Firmware, registry: &Registry) -> Self {
+        let (old_gbl, new_gbl) = (inner_gbl(old), inner_gbl(new));
+        let (old_info, new_info) = (
+            old_gbl.as_ref().and_then(Gbl::application_info),
+            new_gbl.as_ref().and_then(Gbl::application_info),
+        );
+
+        let mut sections = vec![
+            Section::new("Version", version_fields(old, new)),
+            Section::new("OTA header", header_fields(old, new, registry)),
+            Section::new("OTA tags", ota_tag_fields(old, new)),
+            Section::new(
+                "GBL tags",
+                gbl_tag_fields(old_gbl.as_ref(), new_gbl.as_ref()),
+            ),
+            Section::new("Payload", payload_fields(old, new)),
+            Section::new(
+                "Application info",
+                application_fields(old_info.as_ref(), new_info.as_ref()),
+            ),
+        ];
+        sections.retain(|section| !section.fields.is_empty());
+
+        Self {
+            direction: Direction::new(old, new),
+            application_matches: old_info.zip(new_info).map(|(old, new)| {
+                old.product_id() == new.product_id()
+                    && old.application_type() == new.application_type()
+            }),
+            sections,
+        }
+    }
+}
+
+impl Display for Diff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Direction:         {}", self.direction)?;
+
+        if let Some(application_matches) = self.application_matches {
+            write!(
+                f,
+                "\nApplication:       {}",
+                if application_matches {
+                    "matches"
+                } else {
+                    "differs"
+                }
+            )?;
+        }
+
+        for section in &self.sections {
+            write!(f, "\n\n{section}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The direction of a firmware change.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Direction {
+    Upgrade,
+    Downgrade,
+    Reinstall,
+    Unknown,
+}
+
+impl Direction {
+    /// Determine the direction from the old to the new firmware's version.
+    fn new(old: &Firmware, new: &Firmware) -> Self {
+        match old
+            .version()
+            .zip(new.version())
+            .map(|(old, new)| new.cmp_precedence(&old))
+        {
+            Some(Ordering::Greater) => Self::Upgrade,
+            Some(Ordering::Less) => Self::Downgrade,
+            Some(Ordering::Equal) => Self::Reinstall,
+            None => Self::Unknown,
+        }
+    }
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Upgrade => write!(f, "upgrade"),
+            Self::Downgrade => write!(f, "downgrade"),
+            Self::Reinstall => write!(f, "reinstall"),
+            Self::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// A titled group of compared fields.
+#[derive(Debug, Serialize)]
+struct Section {
+    title: &'static str,
+    fields: Vec<Field>,
+}
+
+impl Section {
+    const fn new(title: &'static str, fields: Vec<Field>) -> Self {
+        Self { title, fields }
+    }
+}
+
+impl Display for Section {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "### {} ###", self.title)?;
+
+        for field in &self.fields {
+            write!(f, "\n{field}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A field compared between the old and the new firmware.
+///
+/// A value of `None` means that the field is not present in the respective firmware.
+#[derive(Debug, Serialize)]
+struct Field {
+    name: String,
+    old: Option<String>,
+    new: Option<String>,
+    changed: bool,
+}
+
+impl Field {
+    fn new(name: impl Into<String>, old: Option<String>, new: Option<String>) -> Self {
+        let changed = old != new;
+        Self {
+            name: name.into(),
+            old,
+            new,
+            changed,
+        }
+    }
+}
+
+impl Display for Field {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let old = self.old.as_deref().unwrap_or("-");
+
+        if self.changed {
+            write!(
+                f,
+                "* {:<18}{old} -> {}",
+                format!("{}:", self.name),
+                self.new.as_deref().unwrap_or("-")
+            )
+        } else {
+            write!(f, "  {:<18}{old}", format!("{}:", self.name))
+        }
+    }
+}
+
+/// Return the GBL image wrapped by the firmware, if any.
+fn inner_gbl(firmware: &Firmware) -> Option<Gbl> {
+    match firmware {
+        Firmware::Ota(ota_file) => ota_file
+            .payload()
+            .and_then(|payload| Gbl::try_from(payload.to_vec()).ok()),
+        Firmware::Gbl(gbl) => Some(gbl.clone()),
+        Firmware::Ebl(_) => None,
+    }
+}
+
+/// Compare the firmware types and versions.
+fn version_fields(old: &Firmware, new: &Firmware) -> Vec<Field> {
+    vec![
+        Field::new(
+            "Format",
+            Some(format_name(old).into()),
+            Some(format_name(new).into()),
+        ),
+        Field::new(
+            "Version",
+            old.version().map(|version| version.to_string()),
+            new.version().map(|version| version.to_string()),
+        ),
+    ]
+}
+
+/// Return the name of the firmware's container format.
+const fn format_name(firmware: &Firmware) -> &'static str {
+    match firmware {
+        Firmware::Ota(_) => "OTA",
+        Firmware::Gbl(_) => "GBL",
+        Firmware::Ebl(_) => "EBL",
+    }
+}
+
+/// Compare the OTA header fields, if any of the files is an OTA file.
+fn header_fields(old: &Firmware, new: &Firmware, registry: &Registry) -> Vec<Field> {
+    type Getter = fn(&OtaFile, &Registry) -> Option<String>;
+
+    const FIELDS: [(&str, Getter); 11] = [
+        ("Header version", |ota, _| {
+            Some(format!("{:#06X}", ota.header().version()))
+        }),
+        ("Field control", |ota, _| {
+            Some(format!("{:#06X}", ota.header().field_control().bits()))
+        }),
+        ("Manufacturer", |ota, registry| {
+            Some(
+                registry
+                    .display_manufacturer(ota.header().manufacturer_id())
+                    .to_string(),
+            )
+        }),
+        ("Image type", |ota, registry| {
+            Some(
+                registry
+                    .display_image_type(ota.header().manufacturer_id(), ota.header().image_type())
+                    .to_string(),
+            )
+        }),
+        ("File version", |ota, _| {
+            Some(format!(
+                "{} ({:#010X})",
+                ota.header().decoded_firmware_version(),
+                ota.header().firmware_version()
+            ))
+        }),
+        ("Stack version", |ota, _| {
+            Some(format!("{:#06X}", ota.header().zigbee_stack_version()))
+        }),
+        ("Name", |ota, _| {
+            Some(ota.header().name().trim_end_matches('\0').to_string())
+        }),
+        ("Image size", |ota, _| {
+            Some(ota.header().image_size().to_string())
+        }),
+        ("Credentials", |ota, _| {
+            ota.security_credentials()
+                .map(|credentials| format!("{credentials:#04X}"))
+        }),
+        ("Destination", |ota, _| {
+            ota.upgrade_file_destination().map(ToString::to_string)
+        }),
+        ("Hardware", |ota, _| {
+            ota.hardware_versions()
+                .map(|versions| format!("{:#06X} - {:#06X}", versions.start(), versions.end()))
+        }),
+    ];
+
+    let (old, new) = (ota_file(old), ota_file(new));
+
+    if old.is_none() && new.is_none() {
+        return Vec::new();
+    }
+
+    FIELDS
+        .iter()
+        .map(|(name, getter)| {
+            Field::new(
+                *name,
+                old.and_then(|ota| getter(ota, registry)),
+                new.and_then(|ota| getter(ota, registry)),
+            )
+        })
+        .collect()
+}
+
+/// Return the OTA file, if the firmware is one.
+const fn ota_file(firmware: &Firmware) -> Option<&OtaFile> {
+    match firmware {
+        Firmware::Ota(ota_file) => Some(ota_file),
+        Firmware::Gbl(_) | Firmware::Ebl(_) => None,
+    }
+}
+
+/// Compare the OTA tags, matching tags by ID and occurrence.
+fn ota_tag_fields(old: &Firmware, new: &Firmware) -> Vec<Field> {
+    let tags = |firmware| {
+        ota_file(firmware).map(|ota| {
+            ota.tags()
+                .iter()
+                .map(|tag| (u32::from(tag.id()), tag.data()))
+                .collect::<Vec<_>>()
+        })
+    };
+
+    tag_fields(
+        tags(old).unwrap_or_default(),
+        tags(new).unwrap_or_default(),
+        6,
+    )
+}
+
+/// Compare the GBL tags, matching tags by ID and occurrence.
+fn gbl_tag_fields(old: Option<&Gbl>, new: Option<&Gbl>) -> Vec<Field> {
+    tag_fields(gbl_tags(old), gbl_tags(new), 10)
+}
+
+/// Return the IDs and raw data of the GBL image's tags, if any.
+fn gbl_tags(gbl: Option<&Gbl>) -> Vec<(u32, &[u8])> {
+    gbl.map(|gbl| gbl.raw_tags().collect()).unwrap_or_default()
+}
+
+/// Compare tags, matching tags by ID and occurrence.
+///
+/// Tag IDs are displayed as hex with the given width, including the `0x` prefix.
+fn tag_fields(old: Vec<(u32, &[u8])>, new: Vec<(u32, &[u8])>, width: usize) -> Vec<Field> {
+    let mut tags: BTreeMap<(u32, usize), (Option<String>, Option<String>)> = BTreeMap::new();
+
+    for (id, occurrence, summary) in summarize_tags(old) {
+        tags.entry((id, occurrence)).or_default().0 = Some(summary);
+    }
+
+    for (id, occurrence, summary) in summarize_tags(new) {
+        tags.entry((id, occurrence)).or_default().1 = Some(summary);
+    }
+
+    tags.into_iter()
+        .map(|((id, occurrence), (old, new))| {
+            let name = if occurrence == 0 {
+                format!("Tag {id:#0width$X}")
+            } else {
+                format!("Tag {id:#0width$X} #{occurrence}")
+            };
+            Field::new(name, old, new)
+        })
+        .collect()
+}
+
+/// Summarize tags by their length and hash, numbering repeated tag IDs.
+fn summarize_tags(tags: Vec<(u32, &[u8])>) -> impl Iterator<Item = (u32, usize, String)> {
+    let mut occurrences: BTreeMap<u32, usize> = BTreeMap::new();
+    tags.into_iter().map(move |(id, data)| {
+        let occurrence = occurrences.entry(id).or_default();
+        let summary = (id, *occurrence, summarize(data));
+        *occurrence += 1;
+        summary
+    })
+}
+
+/// Compare the payloads' sizes and hashes.
+fn payload_fields(old: &Firmware, new: &Firmware) -> Vec<Field> {
+    let (old, new) = (old.payload(), new.payload());
+    vec![
+        Field::new(
+            "Size",
+            old.map(|payload| payload.len().to_string()),
+            new.map(|payload| payload.len().to_string()),
+        ),
+        Field::new("SHA-256", old.map(sha256), new.map(sha256)),
+    ]
+}
+
+/// Compare the GBL application info, if any of the files contains one.
+fn application_fields(old: Option<&ApplicationInfo>, new: Option<&ApplicationInfo>) -> Vec<Field> {
+    type Getter = fn(&ApplicationInfo) -> String;
+
+    const FIELDS: [(&str, Getter); 4] = [
+        ("Product ID", |info| Hex(info.product_id()).to_string()),
+        ("Type", |info| info.application_type().to_string()),
+        ("Version", |info| {
+            info.semver().map_or_else(
+                || format!("{:#010X}", info.version()),
+                |semver| format!("{semver} ({:#010X})", info.version()),
+            )
+        }),
+        ("Capabilities", |info| {
+            format!("{:#010X}", info.capabilities())
+        }),
+    ];
+
+    if old.is_none() && new.is_none() {
+        return Vec::new();
+    }
+
+    FIELDS
+        .iter()
+        .map(|(name, getter)| Field::new(*name, old.map(getter), new.map(getter)))
+        .collect()
+}
+
+/// Summarize data by its length and SHA-256 hash.
+fn summarize(data: &[u8]) -> String {
+    let hash = sha256(data);
+    format!(
+        "{} bytes, SHA-256 {}",
+        data.len(),
+        hash.get(..16).unwrap_or(&hash)
+    )
+}
@@ -21,4 +21,4 @@ pub trait ClearBuffer: Read {
     }
 }
 
-impl<T> ClearBuffer for T where T: Read {}
+impl<T> ClearBuffer for T where T: Read + ?Sized {}
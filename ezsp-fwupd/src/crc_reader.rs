@@ -0,0 +1,76 @@
+use std::io::{self, ErrorKind, Read, copy, sink};
+
+use crc::{Crc, Digest};
+
+use crate::FirmwareError;
+
+/// Reader that calculates the CRC32 checksum of the data read and keeps track of the offset.
+pub struct CrcReader<R> {
+    reader: R,
+    digest: Digest<'static, u32>,
+    offset: usize,
+}
+
+impl<R> CrcReader<R>
+where
+    R: Read,
+{
+    /// Create a new reader calculating the checksum using the given CRC algorithm.
+    pub const fn new(reader: R, crc: &'static Crc<u32>) -> Self {
+        Self {
+            reader,
+            digest: crc.digest(),
+            offset: 0,
+        }
+    }
+
+    /// Return the amount of bytes read so far.
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Return the checksum of the bytes read so far.
+    pub fn checksum(&self) -> u32 {
+        self.digest.clone().finalize()
+    }
+
+    /// Fill the buffer, returning the given error if the data ends prematurely.
+    pub fn read_exact_or<E>(&mut self, buffer: &mut [u8], error: E) -> Result<(), FirmwareError>
+    where
+        E: Into<FirmwareError>,
+    {
+        self.read_exact(buffer).map_err(|io_error| {
+            if io_error.kind() == ErrorKind::UnexpectedEof {
+                error.into()
+            } else {
+                io_error.into()
+            }
+        })
+    }
+
+    /// Read and checksum the next `length` bytes without keeping them,
+    /// returning the given error if the data ends prematurely.
+    pub fn skip_or<E>(&mut self, length: u64, error: E) -> Result<(), FirmwareError>
+    where
+        E: Into<FirmwareError>,
+    {
+        if copy(&mut self.by_ref().take(length), &mut sink())? == length {
+            Ok(())
+        } else {
+            Err(error.into())
+        }
+    }
+}
+
+impl<R> Read for CrcReader<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let size = self.reader.read(buf)?;
+        let data = buf.get(..size).unwrap_or_default();
+        self.digest.update(data);
+        self.offset = self.offset.saturating_add(size);
+        Ok(size)
+    }
+}
@@ -0,0 +1,346 @@
+//! Legacy Ember Bootloader (EBL) images.
+
+use std::fmt::Display;
+use std::io::Read;
+use std::ops::Range;
+
+use crc::{CRC_32_ISO_HDLC, Crc};
+
+pub use self::error::EblError;
+pub use self::header::Header;
+pub use self::tag::Tag;
+use crate::FirmwareError;
+use crate::crc_reader::CrcReader;
+
+/// The first four bytes of an EBL image, i.e. the header tag ID and its length.
+pub const MAGIC: [u8; 4] = [0x00, 0x00, 0x00, 0x8C];
+
+const HEADER_TAG_ID: u16 = 0x0000;
+const END_TAG_ID: u16 = 0xFC04;
+const TAG_HEADER_SIZE: usize = 2 + 2;
+const CRC_SIZE: usize = 4;
+const CRC: Crc<u32> = Crc::<u32>::new(&CRC_32_ISO_HDLC);
+
+mod error;
+mod header;
+mod tag;
+
+/// A legacy Ember Bootloader (EBL) image.
+///
+/// Other than GBL images, EBL tags are stored in big endian.
+/// The image is kept as-is, so that it can be sent to the bootloader unchanged.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Ebl {
+    bytes: Vec<u8>,
+    tags: Vec<(u16, Range<usize>)>,
+}
+
+impl Ebl {
+    /// Verify the structure and the CRC32 checksum of an EBL image without taking ownership of it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EblError`] if the image is malformed or its checksum does not match.
+    pub fn verify(bytes: &[u8]) -> Result<(), EblError> {
+        parse_tags(bytes).map(drop)
+    }
+
+    /// Verify the structure and the CRC32 checksum of an EBL image read from the given reader.
+    ///
+    /// Unlike [`Ebl::verify`], the tags are checksummed without being decoded,
+    /// so that the image need not be loaded into memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`FirmwareError`] if reading fails, the image is malformed or its checksum does not match.
+    pub fn verify_reader<R>(reader: R) -> Result<(), FirmwareError>
+    where
+        R: Read,
+    {
+        let mut reader = CrcReader::new(reader, &CRC);
+        let mut is_first = true;
+
+        loop {
+            let offset = reader.offset();
+            let missing = if is_first {
+                EblError::InvalidHeader
+            } else {
+                EblError::MissingEndTag
+            };
+            let mut id = [0; 2];
+            reader.read_exact_or(&mut id, missing)?;
+            let mut length = [0; 2];
+            reader.read_exact_or(&mut length, missing)?;
+            let (id, length) = (u16::from_be_bytes(id), u16::from_be_bytes(length));
+
+            if is_first && id != HEADER_TAG_ID {
+                return Err(EblError::InvalidHeader.into());
+            }
+
+            if id == END_TAG_ID {
+                return verify_end_tag(&mut reader, id, length, offset);
+            }
+
+            reader.skip_or(length.into(), EblError::Truncated { offset })?;
+            is_first = false;
+        }
+    }
+
+    /// Return the raw bytes of the image.
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Convert the image into its raw bytes.
+    #[must_use]
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    /// Return an iterator over the image's tags.
+    pub fn tags(&self) -> impl Iterator<Item = Tag<'_>> {
+        self.tags
+            .iter()
+            .map(|(id, range)| Tag::decode(*id, self.bytes.get(range.clone()).unwrap_or_default()))
+    }
+
+    /// Return the EBL header.
+    #[must_use]
+    pub fn header(&self) -> Option<Header<'_>> {
+        self.tags().find_map(|tag| match tag {
+            Tag::Header(header) => Some(header),
+            _ => None,
+        })
+    }
+}
+
+impl Display for Ebl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "EBL image size:    {}", self.bytes.len())?;
+
+        for tag in self.tags() {
+            write!(f, "\nEBL tag {:#06X}:    {tag}", tag.id())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl TryFrom<Vec<u8>> for Ebl {
+    type Error = EblError;
+
+    /// Parse an EBL image and verify its CRC32 checksum.
+    fn try_from(bytes: Vec<u8>) -> Result<Self, Self::Error> {
+        let tags = parse_tags(&bytes)?;
+        Ok(Self { bytes, tags })
+    }
+}
+
+/// Verify the CRC32 checksum stored in the end tag against the data read so far.
+fn verify_end_tag<R>(
+    reader: &mut CrcReader<R>,
+    id: u16,
+    length: u16,
+    offset: usize,
+) -> Result<(), FirmwareError>
+where
+    R: Read,
+{
+    if usize::from(length) != CRC_SIZE {
+        return Err(EblError::MalformedTag { id, offset }.into());
+    }
+
+    let calculated = reader.checksum();
+    let mut expected = [0; CRC_SIZE];
+    reader.read_exact_or(&mut expected, EblError::Truncated { offset })?;
+    let expected = u32::from_le_bytes(expected);
+
+    if expected == calculated {
+        Ok(())
+    } else {
+        Err(EblError::CrcMismatch {
+            expected,
+            calculated,
+        }
+        .into())
+    }
+}
+
+/// Parse the tags of an EBL image and verify its CRC32 checksum.
+///
+/// Returns the tag IDs along with the range of their data within the image.
+/// Any data following the end tag, such as padding, is ignored.
+fn parse_tags(bytes: &[u8]) -> Result<Vec<(u16, Range<usize>)>, EblError> {
+    let mut tags = Vec::new();
+    let mut offset = 0;
+
+    loop {
+        let (id, length) = bytes
+            .get(offset..)
+            .and_then(<[u8]>::split_first_chunk::<2>)
+            .and_then(|(id, rest)| {
+                rest.first_chunk::<2>()
+                    .map(|length| (u16::from_be_bytes(*id), u16::from_be_bytes(*length)))
+            })
+            .ok_or(if tags.is_empty() {
+                EblError::InvalidHeader
+            } else {
+                EblError::MissingEndTag
+            })?;
+
+        let start = offset + TAG_HEADER_SIZE;
+        let end = start + usize::from(length);
+
+        if end > bytes.len() {
+            return Err(EblError::Truncated { offset });
+        }
+
+        let tag = Tag::decode(id, bytes.get(start..end).unwrap_or_default());
+
+        if tags.is_empty() && !matches!(tag, Tag::Header(_)) {
+            return Err(EblError::InvalidHeader);
+        }
+
+        if tag.is_malformed() {
+            return Err(EblError::MalformedTag { id, offset });
+        }
+
+        tags.push((id, start..end));
+
+        if let Tag::End { crc: expected } = tag {
+            let calculated = CRC.checksum(bytes.get(..end - CRC_SIZE).unwrap_or_default());
+
+            if expected != calculated {
+                return Err(EblError::CrcMismatch {
+                    expected,
+                    calculated,
+                });
+            }
+
+            return Ok(tags);
+        }
+
+        offset = end;
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::{CRC, END_TAG_ID, Ebl, EblError, HEADER_TAG_ID, Header, Tag};
+
+    /// Build an EBL header tag's data.
+    fn header() -> Vec<u8> {
+        let mut header = vec![0x00, 0x01, 0xE3, 0x50, 0x00, 0x00, 0x40, 0x00];
+        header.extend_from_slice(&0x1234_5678_u32.to_be_bytes());
+        header.extend_from_slice(&[0xAB; 128]);
+        header
+    }
+
+    /// Build an EBL image from the given tags, appending an end tag with a valid CRC32.
+    pub fn ebl(tags: &[(u16, &[u8])]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        for (id, data) in tags {
+            bytes.extend_from_slice(&id.to_be_bytes());
+            bytes.extend_from_slice(&u16::try_from(data.len()).unwrap().to_be_bytes());
+            bytes.extend_from_slice(data);
+        }
+
+        bytes.extend_from_slice(&END_TAG_ID.to_be_bytes());
+        bytes.extend_from_slice(&4_u16.to_be_bytes());
+        let crc = CRC.checksum(&bytes);
+        bytes.extend_from_slice(&crc.to_le_bytes());
+        bytes
+    }
+
+    /// Build a minimal valid EBL image.
+    pub fn minimal_ebl() -> Vec<u8> {
+        ebl(&[
+            (HEADER_TAG_ID, &header()),
+            (0xFE01, &[0x00, 0x00, 0x40, 0x00, 0xAA, 0xBB]),
+        ])
+    }
+
+    #[test]
+    fn test_parse() {
+        let bytes = minimal_ebl();
+        assert_eq!(bytes.first_chunk(), Some(&super::MAGIC));
+
+        let ebl = Ebl::try_from(bytes.clone()).unwrap();
+        assert_eq!(ebl.as_bytes(), bytes);
+
+        let header = ebl.header().unwrap();
+        assert_eq!(header.version(), 0x0001);
+        assert_eq!(header.flash_address(), 0x0000_4000);
+        assert_eq!(header.aat_crc(), 0x1234_5678);
+        assert_eq!(header.aat(), &[0xAB; 128]);
+
+        let tags: Vec<Tag<'_>> = ebl.tags().collect();
+        assert_eq!(tags.len(), 3);
+        assert_eq!(
+            tags[1],
+            Tag::Program {
+                flash_start_address: 0x0000_4000,
+                data: &[0xAA, 0xBB]
+            }
+        );
+        assert!(matches!(tags[2], Tag::End { .. }));
+    }
+
+    #[test]
+    fn test_padding() {
+        let mut bytes = minimal_ebl();
+        bytes.extend_from_slice(&[0xFF; 37]);
+        assert!(Ebl::try_from(bytes).is_ok());
+    }
+
+    #[test]
+    fn test_crc_mismatch() {
+        let mut bytes = minimal_ebl();
+        let index = bytes.len() - 12;
+        bytes[index] ^= 0xFF;
+        assert!(matches!(
+            Ebl::try_from(bytes),
+            Err(EblError::CrcMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_truncated() {
+        let mut bytes = minimal_ebl();
+        bytes.truncate(bytes.len() - 2);
+        assert_eq!(
+            Ebl::try_from(bytes),
+            Err(EblError::Truncated {
+                offset: 4 + Header::SIZE + 4 + 6
+            })
+        );
+    }
+
+    #[test]
+    fn test_missing_end_tag() {
+        let mut bytes = minimal_ebl();
+        bytes.truncate(bytes.len() - 8);
+        assert_eq!(Ebl::try_from(bytes), Err(EblError::MissingEndTag));
+    }
+
+    #[test]
+    fn test_invalid_header() {
+        let mut bytes = minimal_ebl();
+        bytes[6] = 0x00;
+        assert_eq!(Ebl::try_from(bytes), Err(EblError::InvalidHeader));
+    }
+
+    #[test]
+    fn test_malformed_tag() {
+        let bytes = ebl(&[(HEADER_TAG_ID, &header()), (0xFE01, &[0x00, 0x00])]);
+        assert_eq!(
+            Ebl::try_from(bytes),
+            Err(EblError::MalformedTag {
+                id: 0xFE01,
+                offset: 4 + Header::SIZE
+            })
+        );
+    }
+}
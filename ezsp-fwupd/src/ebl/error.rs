@@ -0,0 +1,51 @@
+use std::fmt::Display;
+
+/// Errors that can occur when parsing an [`Ebl`](super::Ebl) image.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum EblError {
+    /// The image does not start with a valid EBL header tag.
+    InvalidHeader,
+    /// A tag at the given offset exceeds the image's size.
+    Truncated {
+        /// The offset of the tag.
+        offset: usize,
+    },
+    /// A tag's data does not match the layout of its tag ID.
+    MalformedTag {
+        /// The tag ID.
+        id: u16,
+        /// The offset of the tag.
+        offset: usize,
+    },
+    /// The image ends without an end tag.
+    MissingEndTag,
+    /// The CRC32 checksum of the image does not match the one stored in the end tag.
+    CrcMismatch {
+        /// The checksum stored in the end tag.
+        expected: u32,
+        /// The checksum calculated over the image.
+        calculated: u32,
+    },
+}
+
+impl Display for EblError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidHeader => write!(f, "Invalid EBL header"),
+            Self::Truncated { offset } => write!(f, "EBL tag at offset {offset} is truncated"),
+            Self::MalformedTag { id, offset } => {
+                write!(f, "EBL tag {id:#06X} at offset {offset} is malformed")
+            }
+            Self::MissingEndTag => write!(f, "EBL image has no end tag"),
+            Self::CrcMismatch {
+                expected,
+                calculated,
+            } => write!(
+                f,
+                "EBL CRC32 mismatch: expected {expected:#010X}, calculated {calculated:#010X}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for EblError {}
@@ -0,0 +1,85 @@
+use std::fmt::Display;
+
+/// Signature identifying a valid EBL header.
+pub const SIGNATURE: u16 = 0xE350;
+
+const AAT_SIZE: usize = 128;
+
+/// Represents the header tag of an EBL image.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Header<'data> {
+    version: u16,
+    signature: u16,
+    flash_address: u32,
+    aat_crc: u32,
+    aat: &'data [u8; AAT_SIZE],
+}
+
+impl<'data> Header<'data> {
+    /// Size of the header tag's data in bytes.
+    pub const SIZE: usize = 2 + 2 + 4 + 4 + AAT_SIZE;
+
+    /// Parse the header from the header tag's big endian data.
+    ///
+    /// Returns `None` if the data has the wrong size or the signature does not match.
+    #[must_use]
+    pub fn from_be_bytes(data: &'data [u8]) -> Option<Self> {
+        let (version, rest) = data.split_first_chunk()?;
+        let (signature, rest) = rest.split_first_chunk()?;
+        let (flash_address, rest) = rest.split_first_chunk()?;
+        let (aat_crc, aat) = rest.split_first_chunk()?;
+        let signature = u16::from_be_bytes(*signature);
+
+        if signature != SIGNATURE {
+            return None;
+        }
+
+        Some(Self {
+            version: u16::from_be_bytes(*version),
+            signature,
+            flash_address: u32::from_be_bytes(*flash_address),
+            aat_crc: u32::from_be_bytes(*aat_crc),
+            aat: aat.try_into().ok()?,
+        })
+    }
+
+    /// Return the version of the EBL format.
+    #[must_use]
+    pub const fn version(&self) -> u16 {
+        self.version
+    }
+
+    /// Return the signature.
+    #[must_use]
+    pub const fn signature(&self) -> u16 {
+        self.signature
+    }
+
+    /// Return the flash address of the application address table (AAT).
+    #[must_use]
+    pub const fn flash_address(&self) -> u32 {
+        self.flash_address
+    }
+
+    /// Return the CRC32 checksum of the application address table (AAT).
+    #[must_use]
+    pub const fn aat_crc(&self) -> u32 {
+        self.aat_crc
+    }
+
+    /// Return the application address table (AAT).
+    #[must_use]
+    pub const fn aat(&self) -> &'data [u8; AAT_SIZE] {
+        self.aat
+    }
+}
+
+impl Display for Header<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "version {:#06X}, flash address {:#010X}, AAT CRC32 {:#010X}",
+            self.version, self.flash_address, self.aat_crc
+        )
+    }
+}
@@ -0,0 +1,176 @@
+use std::fmt::Display;
+
+use super::{END_TAG_ID, HEADER_TAG_ID};
+use crate::ebl::Header;
+use crate::hex::Hex;
+
+const PROG: u16 = 0xFE01;
+const MFGPROG: u16 = 0x02FE;
+const ERASEPROG: u16 = 0xFD03;
+const METADATA: u16 = 0xF608;
+const ENCRYPTION_HEADER: u16 = 0xFB05;
+const ENCRYPTION_INIT: u16 = 0xFA06;
+const ENCRYPTED_DATA: u16 = 0xF907;
+const ENCRYPTION_MAC: u16 = 0xF709;
+
+/// A tag of an EBL image.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Tag<'data> {
+    /// The EBL header.
+    Header(Header<'data>),
+    /// Program data to be written to flash.
+    Program {
+        /// The flash address to write the data to.
+        flash_start_address: u32,
+        /// The program data.
+        data: &'data [u8],
+    },
+    /// Program data to be written to the manufacturing area of the flash.
+    ManufacturingProgram {
+        /// The flash address to write the data to.
+        flash_start_address: u32,
+        /// The program data.
+        data: &'data [u8],
+    },
+    /// Program data to be written to flash after erasing it.
+    EraseProgram {
+        /// The flash address to write the data to.
+        flash_start_address: u32,
+        /// The program data.
+        data: &'data [u8],
+    },
+    /// Application-specific metadata.
+    Metadata(&'data [u8]),
+    /// Marks the start of the encrypted part of the image.
+    EncryptionHeader(&'data [u8]),
+    /// Initialization data of the encryption.
+    EncryptionInit(&'data [u8]),
+    /// Encrypted EBL tags.
+    EncryptedData(&'data [u8]),
+    /// The message authentication code of the encrypted data.
+    EncryptionMac(&'data [u8]),
+    /// The end tag containing the CRC32 checksum over all preceding data.
+    End {
+        /// The CRC32 checksum.
+        crc: u32,
+    },
+    /// A tag with an unknown tag ID.
+    Unknown {
+        /// The tag ID.
+        id: u16,
+        /// The tag's raw data.
+        data: &'data [u8],
+    },
+    /// A tag whose data does not match the layout of its tag ID.
+    Malformed {
+        /// The tag ID.
+        id: u16,
+        /// The tag's raw data.
+        data: &'data [u8],
+    },
+}
+
+impl<'data> Tag<'data> {
+    /// Decode a tag from its ID and data.
+    #[must_use]
+    pub fn decode(id: u16, data: &'data [u8]) -> Self {
+        match id {
+            HEADER_TAG_ID => Header::from_be_bytes(data).map(Self::Header),
+            PROG => split_u32(data).map(|(flash_start_address, data)| Self::Program {
+                flash_start_address,
+                data,
+            }),
+            MFGPROG => {
+                split_u32(data).map(|(flash_start_address, data)| Self::ManufacturingProgram {
+                    flash_start_address,
+                    data,
+                })
+            }
+            ERASEPROG => split_u32(data).map(|(flash_start_address, data)| Self::EraseProgram {
+                flash_start_address,
+                data,
+            }),
+            METADATA => Some(Self::Metadata(data)),
+            ENCRYPTION_HEADER => Some(Self::EncryptionHeader(data)),
+            ENCRYPTION_INIT => Some(Self::EncryptionInit(data)),
+            ENCRYPTED_DATA => Some(Self::EncryptedData(data)),
+            ENCRYPTION_MAC => Some(Self::EncryptionMac(data)),
+            // The CRC is stored in little endian, so that the running CRC over the whole image yields the residue.
+            END_TAG_ID => data.try_into().ok().map(|crc| Self::End {
+                crc: u32::from_le_bytes(crc),
+            }),
+            id => Some(Self::Unknown { id, data }),
+        }
+        .unwrap_or(Self::Malformed { id, data })
+    }
+
+    /// Return the tag ID.
+    #[must_use]
+    pub const fn id(&self) -> u16 {
+        match self {
+            Self::Header(_) => HEADER_TAG_ID,
+            Self::Program { .. } => PROG,
+            Self::ManufacturingProgram { .. } => MFGPROG,
+            Self::EraseProgram { .. } => ERASEPROG,
+            Self::Metadata(_) => METADATA,
+            Self::EncryptionHeader(_) => ENCRYPTION_HEADER,
+            Self::EncryptionInit(_) => ENCRYPTION_INIT,
+            Self::EncryptedData(_) => ENCRYPTED_DATA,
+            Self::EncryptionMac(_) => ENCRYPTION_MAC,
+            Self::End { .. } => END_TAG_ID,
+            Self::Unknown { id, .. } | Self::Malformed { id, .. } => *id,
+        }
+    }
+
+    /// Returns whether the tag's data does not match the layout of its tag ID.
+    #[must_use]
+    pub const fn is_malformed(&self) -> bool {
+        matches!(self, Self::Malformed { .. })
+    }
+}
+
+impl Display for Tag<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Header(header) => write!(f, "Header: {header}"),
+            Self::Program {
+                flash_start_address,
+                data,
+            } => write!(
+                f,
+                "Program data: address {flash_start_address:#010X} ({} bytes)",
+                data.len()
+            ),
+            Self::ManufacturingProgram {
+                flash_start_address,
+                data,
+            } => write!(
+                f,
+                "Manufacturing program data: address {flash_start_address:#010X} ({} bytes)",
+                data.len()
+            ),
+            Self::EraseProgram {
+                flash_start_address,
+                data,
+            } => write!(
+                f,
+                "Erase and program data: address {flash_start_address:#010X} ({} bytes)",
+                data.len()
+            ),
+            Self::Metadata(data) => write!(f, "Metadata ({} bytes)", data.len()),
+            Self::EncryptionHeader(data) => write!(f, "Encryption header ({} bytes)", data.len()),
+            Self::EncryptionInit(data) => write!(f, "Encryption init ({} bytes)", data.len()),
+            Self::EncryptedData(data) => write!(f, "Encrypted data ({} bytes)", data.len()),
+            Self::EncryptionMac(data) => write!(f, "Encryption MAC: {}", Hex(data)),
+            Self::End { crc } => write!(f, "End: CRC32 {crc:#010X}"),
+            Self::Unknown { data, .. } => write!(f, "Unknown ({} bytes)", data.len()),
+            Self::Malformed { data, .. } => write!(f, "Malformed ({} bytes)", data.len()),
+        }
+    }
+}
+
+/// Split a big endian `u32` from the start of the data.
+fn split_u32(data: &[u8]) -> Option<(u32, &[u8])> {
+    data.split_first_chunk()
+        .map(|(value, rest)| (u32::from_be_bytes(*value), rest))
+}
@@ -0,0 +1,216 @@
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+
+use semver::Version;
+
+pub use self::error::FirmwareError;
+use crate::OtaFile;
+use crate::ebl::{self, Ebl};
+use crate::gbl::{self, Gbl};
+use crate::signature::{PublicKey, SignatureError};
+
+const OTA_MAGIC: u32 = 0x0BEE_F11E;
+const EBL_MAGIC: u32 = u32::from_le_bytes(ebl::MAGIC);
+
+mod error;
+
+/// A firmware image in one of the supported container formats.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Firmware {
+    /// A Zigbee OTA file wrapping the actual firmware image.
+    Ota(OtaFile),
+    /// A raw Gecko Bootloader (GBL) image.
+    Gbl(Gbl),
+    /// A raw legacy Ember Bootloader (EBL) image.
+    Ebl(Ebl),
+}
+
+impl Firmware {
+    /// Return the image that is to be sent to the bootloader, if present.
+    #[must_use]
+    pub fn payload(&self) -> Option<&[u8]> {
+        match self {
+            Self::Ota(ota_file) => ota_file.payload(),
+            Self::Gbl(gbl) => Some(gbl.as_bytes()),
+            Self::Ebl(ebl) => Some(ebl.as_bytes()),
+        }
+    }
+
+    /// Convert the firmware into the image that is to be sent to the bootloader, if present.
+    #[must_use]
+    pub fn into_payload(self) -> Option<Vec<u8>> {
+        match self {
+            Self::Ota(ota_file) => ota_file.into_payload(),
+            Self::Gbl(gbl) => Some(gbl.into_bytes()),
+            Self::Ebl(ebl) => Some(ebl.into_bytes()),
+        }
+    }
+
+    /// Return the firmware version stored in the image, if present.
+    ///
+    /// For OTA files, this is the decoded file version of the OTA header.
+    /// For GBL images, this is the version of the application info tag, if it can be decoded.
+    /// EBL images do not carry a version.
+    #[must_use]
+    pub fn version(&self) -> Option<Version> {
+        match self {
+            Self::Ota(ota_file) => Some(ota_file.header().decoded_firmware_version().semver()),
+            Self::Gbl(gbl) => gbl.application_info().and_then(|info| info.semver()),
+            Self::Ebl(_) => None,
+        }
+    }
+
+    /// Return the range of hardware versions supported by the image, if present.
+    ///
+    /// Only OTA files may restrict the supported hardware versions.
+    #[must_use]
+    pub const fn hardware_versions(&self) -> Option<&RangeInclusive<u16>> {
+        match self {
+            Self::Ota(ota_file) => ota_file.hardware_versions(),
+            Self::Gbl(_) | Self::Ebl(_) => None,
+        }
+    }
+
+    /// Verify the firmware image's ECDSA signature against the given public key.
+    ///
+    /// OTA files without a signature sub-element are verified by the signature of the wrapped GBL image, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`SignatureError`] if the image is not signed or the signature does not match.
+    pub fn verify_signature(&self, key: &PublicKey) -> Result<(), SignatureError> {
+        match self {
+            Self::Ota(ota_file) => match ota_file.verify_signature(key) {
+                Err(SignatureError::Unsigned) => ota_file
+                    .payload()
+                    .and_then(|payload| Gbl::try_from(payload.to_vec()).ok())
+                    .ok_or(SignatureError::Unsigned)?
+                    .verify_signature(key),
+                result => result,
+            },
+            Self::Gbl(gbl) => gbl.verify_signature(key),
+            Self::Ebl(_) => Err(SignatureError::Unsupported),
+        }
+    }
+}
+
+impl Display for Firmware {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ota(ota_file) => ota_file.fmt(f),
+            Self::Gbl(gbl) => gbl.fmt(f),
+            Self::Ebl(ebl) => ebl.fmt(f),
+        }
+    }
+}
+
+impl TryFrom<Vec<u8>> for Firmware {
+    type Error = FirmwareError;
+
+    /// Parse a firmware image, detecting its format by its magic number.
+    ///
+    /// GBL and EBL images, including those wrapped in an OTA file, are verified against their CRC32 checksum.
+    fn try_from(bytes: Vec<u8>) -> Result<Self, Self::Error> {
+        let magic = bytes
+            .first_chunk()
+            .copied()
+            .map(u32::from_le_bytes)
+            .ok_or(FirmwareError::UnknownFormat(None))?;
+
+        match magic {
+            OTA_MAGIC => {
+                let ota_file = OtaFile::try_from(bytes.as_slice())
+                    .map_err(FirmwareError::Ota)?
+                    .validate()
+                    .map_err(FirmwareError::InvalidOta)?;
+
+                if let Some(payload) = ota_file.payload() {
+                    if payload.first_chunk() == Some(&gbl::HEADER_TAG_ID.to_le_bytes()) {
+                        Gbl::verify(payload)?;
+                    } else if payload.first_chunk() == Some(&ebl::MAGIC) {
+                        Ebl::verify(payload)?;
+                    }
+                }
+
+                Ok(Self::Ota(ota_file))
+            }
+            gbl::HEADER_TAG_ID => Gbl::try_from(bytes).map(Self::Gbl).map_err(Into::into),
+            EBL_MAGIC => Ebl::try_from(bytes).map(Self::Ebl).map_err(Into::into),
+            other => Err(FirmwareError::UnknownFormat(Some(other))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use le_stream::ToLeStream;
+
+    use super::{Firmware, FirmwareError};
+    use crate::ebl::EblError;
+    use crate::ebl::tests::minimal_ebl;
+    use crate::gbl::GblError;
+    use crate::gbl::tests::minimal_gbl;
+    use crate::ota_file::{OtaFileBuilder, Tag};
+
+    fn ota_file(payload: Vec<u8>) -> Vec<u8> {
+        OtaFileBuilder::new(0x1002, 0x0001, 0x0000_0001)
+            .tag(Tag::new(Tag::UPGRADE_IMAGE, payload).unwrap())
+            .build()
+            .unwrap()
+            .to_le_stream()
+            .collect()
+    }
+
+    #[test]
+    fn test_detect_gbl() {
+        let firmware = Firmware::try_from(minimal_gbl()).unwrap();
+        assert!(matches!(firmware, Firmware::Gbl(_)));
+        assert_eq!(firmware.payload(), Some(minimal_gbl().as_slice()));
+    }
+
+    #[test]
+    fn test_detect_ota() {
+        let firmware = Firmware::try_from(ota_file(minimal_gbl())).unwrap();
+        assert!(matches!(firmware, Firmware::Ota(_)));
+        assert_eq!(firmware.payload(), Some(minimal_gbl().as_slice()));
+    }
+
+    #[test]
+    fn test_corrupted_gbl_in_ota() {
+        let mut gbl = minimal_gbl();
+        gbl[30] ^= 0xFF;
+        assert!(matches!(
+            Firmware::try_from(ota_file(gbl)),
+            Err(FirmwareError::Gbl(GblError::CrcMismatch { .. }))
+        ));
+    }
+
+    #[test]
+    fn test_detect_ebl() {
+        let firmware = Firmware::try_from(minimal_ebl()).unwrap();
+        assert!(matches!(firmware, Firmware::Ebl(_)));
+        assert_eq!(firmware.payload(), Some(minimal_ebl().as_slice()));
+    }
+
+    #[test]
+    fn test_corrupted_ebl_in_ota() {
+        let mut ebl = minimal_ebl();
+        ebl[20] ^= 0xFF;
+        assert!(matches!(
+            Firmware::try_from(ota_file(ebl)),
+            Err(FirmwareError::Ebl(EblError::CrcMismatch { .. }))
+        ));
+    }
+
+    #[test]
+    fn test_detect_unknown() {
+        assert!(matches!(
+            Firmware::try_from(vec![0xDE, 0xAD, 0xBE, 0xEF]),
+            Err(FirmwareError::UnknownFormat(Some(0xEFBE_ADDE)))
+        ));
+        assert!(matches!(
+            Firmware::try_from(vec![0xEB, 0x17]),
+            Err(FirmwareError::UnknownFormat(None))
+        ));
+    }
+}
@@ -0,0 +1,69 @@
+use std::fmt::Display;
+
+use crate::ebl::EblError;
+use crate::gbl::GblError;
+use crate::ota_file::{OtaError, ValidationError};
+
+/// Errors that can occur when loading a [`Firmware`](crate::Firmware) image.
+#[derive(Debug)]
+pub enum FirmwareError {
+    /// Reading the firmware image failed.
+    Io(std::io::Error),
+    /// The image's magic number does not match any supported format.
+    ///
+    /// Contains the magic number, if the image is at least four bytes long.
+    UnknownFormat(Option<u32>),
+    /// The OTA file could not be parsed.
+    Ota(OtaError),
+    /// The OTA file failed validation.
+    InvalidOta(ValidationError),
+    /// The GBL image is malformed or corrupted.
+    Gbl(GblError),
+    /// The EBL image is malformed or corrupted.
+    Ebl(EblError),
+}
+
+impl Display for FirmwareError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "Failed to read firmware image: {error}"),
+            Self::UnknownFormat(Some(magic)) => write!(f, "Unknown firmware format: {magic:#010X}"),
+            Self::UnknownFormat(None) => write!(f, "Firmware image too short"),
+            Self::Ota(error) => error.fmt(f),
+            Self::InvalidOta(error) => error.fmt(f),
+            Self::Gbl(error) => error.fmt(f),
+            Self::Ebl(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for FirmwareError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Ota(error) => Some(error),
+            Self::InvalidOta(error) => Some(error),
+            Self::Gbl(error) => Some(error),
+            Self::Ebl(error) => Some(error),
+            Self::UnknownFormat(_) => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for FirmwareError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<GblError> for FirmwareError {
+    fn from(error: GblError) -> Self {
+        Self::Gbl(error)
+    }
+}
+
+impl From<EblError> for FirmwareError {
+    fn from(error: EblError) -> Self {
+        Self::Ebl(error)
+    }
+}
@@ -11,6 +11,12 @@ pub trait FlashProgress {
     /// Increases the progress bar by one step.
     fn increase(&self);
 
+    /// Sets the total amount of steps of the progress bar.
+    fn set_length(&self, length: usize);
+
+    /// Multiplies the total amount of steps of the progress bar by the given factor.
+    fn scale_length(&self, factor: usize);
+
     /// Prints a message to the progress bar or logs it if no progress bar is available.
     fn println(&self, msg: impl AsRef<str>);
 }
@@ -30,6 +36,21 @@ impl FlashProgress for Option<&ProgressBar> {
         }
     }
 
+    fn set_length(&self, length: usize) {
+        if let Some(progress_bar) = self {
+            progress_bar.set_length(u64::try_from(length).unwrap_or(u64::MAX));
+        }
+    }
+
+    fn scale_length(&self, factor: usize) {
+        if let Some(progress_bar) = self
+            && let Some(length) = progress_bar.length()
+        {
+            progress_bar
+                .set_length(length.saturating_mul(u64::try_from(factor).unwrap_or(u64::MAX)));
+        }
+    }
+
     fn println(&self, msg: impl AsRef<str>) {
         if let Some(progress_bar) = self {
             progress_bar.println(msg);
@@ -1,3 +1,4 @@
+use std::io::{ErrorKind, Read};
 use std::time::Duration;
 
 use ashv2::TryCloneNative;
@@ -8,23 +9,36 @@ use serialport::SerialPort;
 pub use self::reset::Reset;
 use self::transmit::Transmit;
 use crate::launch_bootloader::LaunchBootloader;
-pub use crate::xmodem::FrameCount;
-use crate::{ClearBuffer, FlashProgress};
+use crate::preflight::{Preflight, RunPreflight};
+#[cfg(feature = "clap")]
+pub use crate::xmodem::XmodemArgs;
+pub use crate::xmodem::{BlockSize, FrameCount, TransferError, XmodemConfig};
+use crate::{ClearBuffer, FlashProgress, UartParams};
 
 mod reset;
 mod transmit;
 
 /// Trait for firmware update operations using a serial port.
 pub trait Fwupd: Sized {
-    /// Performs a firmware update operation.
+    /// Performs a firmware update operation, streaming the firmware from the given reader.
+    ///
+    /// The update is refused unless the device meets the given preflight requirements,
+    /// which are checked over EZSP using the given UART parameters.
+    /// The firmware is sent according to the given XMODEM configuration.
+    ///
+    /// The configuration is refused if its ACK timeout, or the serial port's timeout if none is set,
+    /// exceeds the handshake timeout, since the handshake could otherwise overshoot its timeout.
     fn fwupd<F>(
         self,
         firmware: F,
+        preflight: &Preflight,
+        uart_params: &UartParams,
+        xmodem: &XmodemConfig,
         timeout: Option<Duration>,
         progress_bar: Option<&ProgressBar>,
     ) -> impl Future<Output = std::io::Result<Self>>
     where
-        F: IntoIterator<Item = u8>;
+        F: Read;
 }
 
 impl<T> Fwupd for T
@@ -34,15 +48,34 @@ where
     async fn fwupd<F>(
         mut self,
         firmware: F,
+        preflight: &Preflight,
+        uart_params: &UartParams,
+        xmodem: &XmodemConfig,
         timeout: Option<Duration>,
         progress_bar: Option<&ProgressBar>,
     ) -> std::io::Result<Self>
     where
-        F: IntoIterator<Item = u8>,
+        F: Read,
     {
+        let ack_timeout = xmodem.ack_timeout().unwrap_or_else(|| self.timeout());
+
+        if ack_timeout > xmodem.handshake_timeout() {
+            return Err(std::io::Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "XMODEM ACK timeout of {ack_timeout:?} exceeds the handshake timeout of {:?}",
+                    xmodem.handshake_timeout()
+                ),
+            ));
+        }
+
+        if !preflight.is_empty() {
+            info!("Checking device compatibility...");
+            self = self.preflight(preflight, uart_params).await?;
+        }
+
         info!("Preparing bootloader...");
         self = self.launch_bootloader().await?;
-        let original_timeout = self.timeout();
 
         if let Some(timeout) = timeout {
             self.set_timeout(timeout)?;
@@ -57,7 +90,7 @@ where
         self.init_stage2()?;
 
         debug!("Transmitting firmware...");
-        self.transmit(firmware, Some(original_timeout), progress_bar)?;
+        self.transmit(firmware, xmodem, Some(ack_timeout), progress_bar)?;
 
         progress_bar.set_message("Firmware update complete, resetting device...");
         self.reset(timeout)?;
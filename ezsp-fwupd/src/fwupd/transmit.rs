@@ -1,3 +1,4 @@
+use std::io::Read;
 use std::time::Duration;
 
 use indicatif::ProgressBar;
@@ -5,7 +6,7 @@ use log::{debug, trace};
 use serialport::SerialPort;
 
 use crate::FlashProgress;
-use crate::xmodem::Send;
+use crate::xmodem::{Send, XmodemConfig};
 
 const INIT_STAGE1: &[u8] = &[0x0A];
 const INIT_STAGE1_RESPONSE_SIZE: usize = 69;
@@ -20,15 +21,16 @@ pub trait Transmit {
     /// Initialize the second stage of the firmware update process.
     fn init_stage2(&mut self) -> std::io::Result<()>;
 
-    /// Transmit the firmware to the device using the XMODEM protocol.
+    /// Transmit the firmware read from the given reader to the device using the XMODEM protocol.
     fn transmit<F>(
         &mut self,
         firmware: F,
+        config: &XmodemConfig,
         timeout: Option<Duration>,
         progress_bar: Option<&ProgressBar>,
     ) -> std::io::Result<()>
     where
-        F: IntoIterator<Item = u8>;
+        F: Read;
 }
 
 impl<T> Transmit for T
@@ -58,11 +60,12 @@ where
     fn transmit<F>(
         &mut self,
         firmware: F,
+        config: &XmodemConfig,
         timeout: Option<Duration>,
         progress_bar: Option<&ProgressBar>,
     ) -> std::io::Result<()>
     where
-        F: IntoIterator<Item = u8>,
+        F: Read,
     {
         if let Some(timeout) = timeout {
             debug!("Setting timeout to {timeout:?}");
@@ -72,7 +75,7 @@ where
         }
 
         progress_bar.set_message("Flashing firmware...");
-        let response = self.send(firmware, progress_bar)?;
+        let response = self.send(firmware, config, progress_bar)?;
         debug!("Firmware sent response: {response:#04X?}");
 
         Ok(())
@@ -0,0 +1,434 @@
+//! Gecko Bootloader (GBL) images.
+
+use std::fmt::Display;
+use std::io::Read;
+use std::ops::Range;
+
+use crc::{CRC_32_ISO_HDLC, Crc};
+
+pub use self::application_info::{ApplicationInfo, ApplicationType};
+pub use self::error::GblError;
+pub use self::header::Header;
+pub use self::tag::Tag;
+use crate::FirmwareError;
+use crate::crc_reader::CrcReader;
+use crate::signature::{PublicKey, SignatureError};
+
+/// Tag ID of the GBL header tag, which also serves as the GBL file's magic number.
+pub const HEADER_TAG_ID: u32 = 0x03A6_17EB;
+
+const END_TAG_ID: u32 = 0xFC04_04FC;
+const TAG_HEADER_SIZE: usize = 4 + 4;
+const CRC_SIZE: usize = 4;
+const CRC: Crc<u32> = Crc::<u32>::new(&CRC_32_ISO_HDLC);
+
+mod application_info;
+mod error;
+mod header;
+mod tag;
+
+/// A Gecko Bootloader (GBL) image.
+///
+/// The image is kept as-is, so that it can be sent to the bootloader unchanged.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Gbl {
+    bytes: Vec<u8>,
+    tags: Vec<(u32, Range<usize>)>,
+}
+
+impl Gbl {
+    /// Verify the structure and the CRC32 checksum of a GBL image without taking ownership of it.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`GblError`] if the image is malformed or its checksum does not match.
+    pub fn verify(bytes: &[u8]) -> Result<(), GblError> {
+        parse_tags(bytes).map(drop)
+    }
+
+    /// Verify the structure and the CRC32 checksum of a GBL image read from the given reader.
+    ///
+    /// Unlike [`Gbl::verify`], the tags are checksummed without being decoded,
+    /// so that the image need not be loaded into memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`FirmwareError`] if reading fails, the image is malformed or its checksum does not match.
+    pub fn verify_reader<R>(reader: R) -> Result<(), FirmwareError>
+    where
+        R: Read,
+    {
+        let mut reader = CrcReader::new(reader, &CRC);
+        let mut is_first = true;
+
+        loop {
+            let offset = reader.offset();
+            let missing = if is_first {
+                GblError::InvalidHeader(None)
+            } else {
+                GblError::MissingEndTag
+            };
+            let mut id = [0; 4];
+            reader.read_exact_or(&mut id, missing)?;
+            let mut length = [0; 4];
+            reader.read_exact_or(&mut length, missing)?;
+            let (id, length) = (u32::from_le_bytes(id), u32::from_le_bytes(length));
+
+            if is_first && id != HEADER_TAG_ID {
+                return Err(GblError::InvalidHeader(Some(id)).into());
+            }
+
+            if id == END_TAG_ID {
+                return verify_end_tag(&mut reader, id, length, offset);
+            }
+
+            reader.skip_or(length.into(), GblError::Truncated { offset })?;
+            is_first = false;
+        }
+    }
+
+    /// Return the raw bytes of the image.
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Convert the image into its raw bytes.
+    #[must_use]
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    /// Return an iterator over the image's tags.
+    pub fn tags(&self) -> impl Iterator<Item = Tag<'_>> {
+        self.tags
+            .iter()
+            .map(|(id, range)| Tag::decode(*id, self.bytes.get(range.clone()).unwrap_or_default()))
+    }
+
+    /// Return an iterator over the IDs and raw data of the image's tags.
+    pub fn raw_tags(&self) -> impl Iterator<Item = (u32, &[u8])> {
+        self.tags
+            .iter()
+            .map(|(id, range)| (*id, self.bytes.get(range.clone()).unwrap_or_default()))
+    }
+
+    /// Return the GBL header.
+    #[must_use]
+    pub fn header(&self) -> Option<Header> {
+        self.tags().find_map(|tag| match tag {
+            Tag::Header(header) => Some(header),
+            _ => None,
+        })
+    }
+
+    /// Verify the image's ECDSA-P256 signature against the given public key.
+    ///
+    /// The signature is calculated over the SHA-256 hash of all data preceding the signature tag.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`SignatureError`] if the image is not signed or the signature does not match.
+    pub fn verify_signature(&self, key: &PublicKey) -> Result<(), SignatureError> {
+        let (start, r, s) = self
+            .tags
+            .iter()
+            .find_map(|(id, range)| {
+                match Tag::decode(*id, self.bytes.get(range.clone()).unwrap_or_default()) {
+                    Tag::SignatureEcdsaP256 { r, s } => Some((range.start - TAG_HEADER_SIZE, r, s)),
+                    _ => None,
+                }
+            })
+            .ok_or(SignatureError::Unsigned)?;
+
+        key.verify_p256(self.bytes.get(..start).unwrap_or_default(), &r, &s)
+    }
+
+    /// Return the application info, if present.
+    #[must_use]
+    pub fn application_info(&self) -> Option<ApplicationInfo> {
+        self.tags().find_map(|tag| match tag {
+            Tag::Application(application_info) => Some(application_info),
+            _ => None,
+        })
+    }
+}
+
+impl Display for Gbl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "GBL image size:    {}", self.bytes.len())?;
+
+        for tag in self.tags() {
+            write!(f, "\nGBL tag {:#010X}: {tag}", tag.id())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl TryFrom<Vec<u8>> for Gbl {
+    type Error = GblError;
+
+    /// Parse a GBL image and verify its CRC32 checksum.
+    fn try_from(bytes: Vec<u8>) -> Result<Self, Self::Error> {
+        let tags = parse_tags(&bytes)?;
+        Ok(Self { bytes, tags })
+    }
+}
+
+/// Verify the CRC32 checksum stored in the end tag against the data read so far.
+fn verify_end_tag<R>(
+    reader: &mut CrcReader<R>,
+    id: u32,
+    length: u32,
+    offset: usize,
+) -> Result<(), FirmwareError>
+where
+    R: Read,
+{
+    if usize::try_from(length).ok() != Some(CRC_SIZE) {
+        return Err(GblError::MalformedTag { id, offset }.into());
+    }
+
+    let calculated = reader.checksum();
+    let mut expected = [0; CRC_SIZE];
+    reader.read_exact_or(&mut expected, GblError::Truncated { offset })?;
+    let expected = u32::from_le_bytes(expected);
+
+    if expected == calculated {
+        Ok(())
+    } else {
+        Err(GblError::CrcMismatch {
+            expected,
+            calculated,
+        }
+        .into())
+    }
+}
+
+/// Parse the tags of a GBL image and verify its CRC32 checksum.
+///
+/// Returns the tag IDs along with the range of their data within the image.
+/// Any data following the end tag is ignored.
+fn parse_tags(bytes: &[u8]) -> Result<Vec<(u32, Range<usize>)>, GblError> {
+    let mut tags = Vec::new();
+    let mut offset = 0;
+
+    loop {
+        let (id, length) = bytes
+            .get(offset..)
+            .and_then(<[u8]>::split_first_chunk::<4>)
+            .and_then(|(id, rest)| {
+                rest.first_chunk::<4>()
+                    .map(|length| (u32::from_le_bytes(*id), u32::from_le_bytes(*length)))
+            })
+            .ok_or(if tags.is_empty() {
+                GblError::InvalidHeader(None)
+            } else {
+                GblError::MissingEndTag
+            })?;
+
+        if tags.is_empty() && id != HEADER_TAG_ID {
+            return Err(GblError::InvalidHeader(Some(id)));
+        }
+
+        let start = offset + TAG_HEADER_SIZE;
+        let end = usize::try_from(length)
+            .ok()
+            .and_then(|length| start.checked_add(length))
+            .filter(|&end| end <= bytes.len())
+            .ok_or(GblError::Truncated { offset })?;
+
+        if Tag::decode(id, bytes.get(start..end).unwrap_or_default()).is_malformed() {
+            return Err(GblError::MalformedTag { id, offset });
+        }
+
+        tags.push((id, start..end));
+
+        if id == END_TAG_ID {
+            let crc_offset = end - CRC_SIZE;
+            let expected = bytes
+                .get(crc_offset..end)
+                .and_then(|crc| crc.try_into().ok())
+                .map(u32::from_le_bytes)
+                .ok_or(GblError::MalformedTag { id, offset })?;
+            let calculated = CRC.checksum(bytes.get(..crc_offset).unwrap_or_default());
+
+            if expected != calculated {
+                return Err(GblError::CrcMismatch {
+                    expected,
+                    calculated,
+                });
+            }
+
+            return Ok(tags);
+        }
+
+        offset = end;
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use p256::ecdsa::signature::Signer;
+    use p256::ecdsa::{Signature, SigningKey};
+
+    use super::{ApplicationType, CRC, END_TAG_ID, Gbl, GblError, HEADER_TAG_ID, Tag};
+    use crate::signature::{PublicKey, SignatureError};
+
+    const HEADER: [u8; 8] = [0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x00];
+    const APPLICATION: [u8; 28] = [
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x03, 0x04, 0x07, 0x00, 0x00, 0x00, 0x00, 0x01, 0x02, 0x03,
+        0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E, 0x0F, 0x10,
+    ];
+
+    /// Build a GBL image from the given tags, appending an end tag with a valid CRC32.
+    pub fn gbl(tags: &[(u32, &[u8])]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        for (id, data) in tags {
+            bytes.extend_from_slice(&id.to_le_bytes());
+            bytes.extend_from_slice(&u32::try_from(data.len()).unwrap().to_le_bytes());
+            bytes.extend_from_slice(data);
+        }
+
+        bytes.extend_from_slice(&END_TAG_ID.to_le_bytes());
+        bytes.extend_from_slice(&4_u32.to_le_bytes());
+        let crc = CRC.checksum(&bytes);
+        bytes.extend_from_slice(&crc.to_le_bytes());
+        bytes
+    }
+
+    /// Build a minimal valid GBL image.
+    pub fn minimal_gbl() -> Vec<u8> {
+        gbl(&[
+            (HEADER_TAG_ID, &HEADER),
+            (0xF40A_0AF4, &APPLICATION),
+            (0xFE01_01FE, &[0x00, 0x00, 0x00, 0x08, 0xAA, 0xBB]),
+        ])
+    }
+
+    #[test]
+    fn test_parse() {
+        let bytes = minimal_gbl();
+        let gbl = Gbl::try_from(bytes.clone()).unwrap();
+        assert_eq!(gbl.as_bytes(), bytes);
+        assert_eq!(gbl.header().unwrap().version(), 0x0300_0000);
+        assert!(!gbl.header().unwrap().is_signed());
+
+        let application_info = gbl.application_info().unwrap();
+        assert_eq!(application_info.application_type(), ApplicationType::ZIGBEE);
+        assert_eq!(application_info.version(), 0x0704_0300);
+        assert_eq!(
+            application_info.semver(),
+            Some(semver::Version::parse("7.4.3+0").unwrap())
+        );
+        assert_eq!(application_info.product_id()[0], 0x01);
+
+        let tags: Vec<Tag<'_>> = gbl.tags().collect();
+        assert_eq!(tags.len(), 4);
+        assert_eq!(
+            tags[2],
+            Tag::Program {
+                flash_start_address: 0x0800_0000,
+                data: &[0xAA, 0xBB]
+            }
+        );
+        assert!(matches!(tags[3], Tag::End { .. }));
+    }
+
+    #[test]
+    fn test_build_counter_version() {
+        let mut application = APPLICATION;
+        application[4..8].copy_from_slice(&1_u32.to_le_bytes());
+        let gbl = Gbl::try_from(gbl(&[
+            (HEADER_TAG_ID, &HEADER),
+            (0xF40A_0AF4, &application),
+        ]))
+        .unwrap();
+        let application_info = gbl.application_info().unwrap();
+        assert_eq!(application_info.version(), 1);
+        assert_eq!(application_info.semver(), None);
+    }
+
+    #[test]
+    fn test_crc_mismatch() {
+        let mut bytes = minimal_gbl();
+        bytes[50] ^= 0xFF;
+        assert!(matches!(
+            Gbl::try_from(bytes),
+            Err(GblError::CrcMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_truncated() {
+        let mut bytes = minimal_gbl();
+        bytes.truncate(bytes.len() - 2);
+        assert_eq!(
+            Gbl::verify(&bytes),
+            Err(GblError::Truncated {
+                offset: bytes.len() - 10
+            })
+        );
+    }
+
+    #[test]
+    fn test_missing_end_tag() {
+        let bytes = minimal_gbl();
+        assert_eq!(
+            Gbl::verify(&bytes[..bytes.len() - 12]),
+            Err(GblError::MissingEndTag)
+        );
+    }
+
+    #[test]
+    fn test_invalid_header() {
+        assert_eq!(
+            Gbl::verify(&[0x1E, 0xF1, 0xEE, 0x0B, 0x00, 0x00, 0x00, 0x00]),
+            Err(GblError::InvalidHeader(Some(0x0BEE_F11E)))
+        );
+        assert_eq!(Gbl::verify(&[]), Err(GblError::InvalidHeader(None)));
+    }
+
+    #[test]
+    fn test_malformed_tag() {
+        let bytes = gbl(&[(HEADER_TAG_ID, &HEADER), (0xF40A_0AF4, &APPLICATION[..27])]);
+        assert_eq!(
+            Gbl::verify(&bytes),
+            Err(GblError::MalformedTag {
+                id: 0xF40A_0AF4,
+                offset: 16
+            })
+        );
+    }
+
+    #[test]
+    fn test_verify_signature() {
+        let signing_key = SigningKey::from_slice(&[0x42; 32]).unwrap();
+        let public_key = PublicKey::from(*signing_key.verifying_key());
+        let tags: [(u32, &[u8]); 2] = [(HEADER_TAG_ID, &HEADER), (0xF40A_0AF4, &APPLICATION)];
+        let unsigned = gbl(&tags);
+        let signature: Signature = signing_key.sign(&unsigned[..unsigned.len() - 12]);
+        let signed = Gbl::try_from(gbl(&[
+            tags[0],
+            tags[1],
+            (0xF70A_0AF7, signature.to_bytes().as_slice()),
+        ]))
+        .unwrap();
+        assert_eq!(signed.verify_signature(&public_key), Ok(()));
+
+        let other_key =
+            PublicKey::from(*SigningKey::from_slice(&[0x24; 32]).unwrap().verifying_key());
+        assert_eq!(
+            signed.verify_signature(&other_key),
+            Err(SignatureError::Mismatch)
+        );
+        assert_eq!(
+            Gbl::try_from(unsigned)
+                .unwrap()
+                .verify_signature(&public_key),
+            Err(SignatureError::Unsigned)
+        );
+    }
+}
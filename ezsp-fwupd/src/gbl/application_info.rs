@@ -0,0 +1,77 @@
+use std::fmt::Display;
+
+use le_stream::FromLeStream;
+use semver::{BuildMetadata, Version};
+
+pub use self::application_type::ApplicationType;
+use crate::hex::Hex;
+
+mod application_type;
+
+/// Represents the application info tag of a GBL image.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, FromLeStream)]
+pub struct ApplicationInfo {
+    application_type: ApplicationType,
+    version: u32,
+    capabilities: u32,
+    product_id: [u8; 16],
+}
+
+impl ApplicationInfo {
+    /// Return the application type flags.
+    #[must_use]
+    pub const fn application_type(&self) -> ApplicationType {
+        self.application_type
+    }
+
+    /// Return the application version.
+    #[must_use]
+    pub const fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Return the application version as a semantic version, if it can be decoded.
+    ///
+    /// The version's format is up to the application.
+    /// `EmberZNet` applications encode their version as `major.minor.patch.special`, one byte each.
+    /// The special version is stored as build metadata.
+    /// Versions with a major version of zero, such as plain build counters, are not decoded.
+    #[must_use]
+    pub fn semver(&self) -> Option<Version> {
+        let [major, minor, patch, special] = self.version.to_be_bytes();
+
+        if major == 0 {
+            return None;
+        }
+
+        Some(Version {
+            build: BuildMetadata::new(&special.to_string()).unwrap_or_default(),
+            ..Version::new(major.into(), minor.into(), patch.into())
+        })
+    }
+
+    /// Return the application capabilities.
+    #[must_use]
+    pub const fn capabilities(&self) -> u32 {
+        self.capabilities
+    }
+
+    /// Return the product ID.
+    #[must_use]
+    pub const fn product_id(&self) -> &[u8; 16] {
+        &self.product_id
+    }
+}
+
+impl Display for ApplicationInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "type {}, version {:#010X}, capabilities {:#010X}, product ID {}",
+            self.application_type,
+            self.version,
+            self.capabilities,
+            Hex(&self.product_id)
+        )
+    }
+}
@@ -0,0 +1,47 @@
+use std::fmt::Display;
+
+use bitflags::bitflags;
+use le_stream::FromLeStream;
+
+/// Represents the application type flags of a GBL application info tag.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, FromLeStream)]
+pub struct ApplicationType(u32);
+
+bitflags! {
+    impl ApplicationType: u32 {
+        /// Zigbee application.
+        const ZIGBEE = 1 << 0;
+        /// Thread application.
+        const THREAD = 1 << 1;
+        /// Flex application.
+        const FLEX = 1 << 2;
+        /// Bluetooth application.
+        const BLUETOOTH = 1 << 3;
+        /// Generic MCU application.
+        const MCU = 1 << 4;
+        /// Bluetooth application running on the host.
+        const BLUETOOTH_APP = 1 << 5;
+        /// Bootloader.
+        const BOOTLOADER = 1 << 6;
+        /// Z-Wave application.
+        const ZWAVE = 1 << 7;
+    }
+}
+
+impl Display for ApplicationType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return write!(f, "{:#010X}", self.bits());
+        }
+
+        for (index, (name, _)) in self.iter_names().enumerate() {
+            if index > 0 {
+                write!(f, " | ")?;
+            }
+
+            write!(f, "{name}")?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,54 @@
+use std::fmt::Display;
+
+/// Errors that can occur when parsing a [`Gbl`](super::Gbl) image.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum GblError {
+    /// The image does not start with a GBL header tag.
+    ///
+    /// Contains the first tag ID, if the image is long enough to contain one.
+    InvalidHeader(Option<u32>),
+    /// A tag at the given offset exceeds the image's size.
+    Truncated {
+        /// The offset of the tag.
+        offset: usize,
+    },
+    /// A tag's data does not match the layout of its tag ID.
+    MalformedTag {
+        /// The tag ID.
+        id: u32,
+        /// The offset of the tag.
+        offset: usize,
+    },
+    /// The image ends without an end tag.
+    MissingEndTag,
+    /// The CRC32 checksum of the image does not match the one stored in the end tag.
+    CrcMismatch {
+        /// The checksum stored in the end tag.
+        expected: u32,
+        /// The checksum calculated over the image.
+        calculated: u32,
+    },
+}
+
+impl Display for GblError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidHeader(Some(id)) => write!(f, "Invalid GBL header tag: {id:#010X}"),
+            Self::InvalidHeader(None) => write!(f, "GBL image too short"),
+            Self::Truncated { offset } => write!(f, "GBL tag at offset {offset} is truncated"),
+            Self::MalformedTag { id, offset } => {
+                write!(f, "GBL tag {id:#010X} at offset {offset} is malformed")
+            }
+            Self::MissingEndTag => write!(f, "GBL image has no end tag"),
+            Self::CrcMismatch {
+                expected,
+                calculated,
+            } => write!(
+                f,
+                "GBL CRC32 mismatch: expected {expected:#010X}, calculated {calculated:#010X}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GblError {}
@@ -0,0 +1,49 @@
+use std::fmt::Display;
+
+use le_stream::FromLeStream;
+
+const TYPE_ENCRYPTION_AES_CCM: u32 = 0x0000_0001;
+const TYPE_SIGNATURE_ECDSA_P256: u32 = 0x0000_0100;
+
+/// Represents the header tag of a GBL image.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, FromLeStream)]
+pub struct Header {
+    version: u32,
+    image_type: u32,
+}
+
+impl Header {
+    /// Return the version of the GBL format.
+    #[must_use]
+    pub const fn version(self) -> u32 {
+        self.version
+    }
+
+    /// Return the image type flags.
+    #[must_use]
+    pub const fn image_type(self) -> u32 {
+        self.image_type
+    }
+
+    /// Returns whether the image is encrypted.
+    #[must_use]
+    pub const fn is_encrypted(self) -> bool {
+        self.image_type & TYPE_ENCRYPTION_AES_CCM != 0
+    }
+
+    /// Returns whether the image is signed.
+    #[must_use]
+    pub const fn is_signed(self) -> bool {
+        self.image_type & TYPE_SIGNATURE_ECDSA_P256 != 0
+    }
+}
+
+impl Display for Header {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "version {:#010X}, type {:#010X}",
+            self.version, self.image_type
+        )
+    }
+}
@@ -0,0 +1,312 @@
+use std::fmt::Display;
+
+use le_stream::FromLeStream;
+
+use super::{END_TAG_ID, HEADER_TAG_ID};
+use crate::gbl::{ApplicationInfo, Header};
+use crate::hex::Hex;
+
+const BOOTLOADER: u32 = 0xF509_09F5;
+const APPLICATION: u32 = 0xF40A_0AF4;
+const METADATA: u32 = 0xF608_08F6;
+const PROG: u32 = 0xFE01_01FE;
+const ERASEPROG: u32 = 0xFD03_03FD;
+const PROG_LZ4: u32 = 0xFD05_05FD;
+const PROG_LZMA: u32 = 0xFD07_07FD;
+const SE_UPGRADE: u32 = 0x5EA6_17EB;
+const VERSION_DEPENDENCY: u32 = 0x76A6_17EB;
+const ENCRYPTION_HEADER: u32 = 0xFB05_05FB;
+const ENCRYPTION_INIT: u32 = 0xFA06_06FA;
+const ENCRYPTED_DATA: u32 = 0xF907_07F9;
+const ENCRYPTION_MAC: u32 = 0xF808_08F8;
+const SIGNATURE_ECDSA_P256: u32 = 0xF70A_0AF7;
+const CERTIFICATE_ECDSA_P256: u32 = 0xF30B_0BF3;
+
+/// A tag of a GBL image.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Tag<'data> {
+    /// The GBL header.
+    Header(Header),
+    /// Information about the contained application.
+    Application(ApplicationInfo),
+    /// A bootloader upgrade.
+    Bootloader {
+        /// The version of the bootloader.
+        version: u32,
+        /// The address to write the bootloader to.
+        address: u32,
+        /// The bootloader image.
+        data: &'data [u8],
+    },
+    /// A Secure Element upgrade.
+    SeUpgrade {
+        /// The size of the upgrade blob.
+        blob_size: u32,
+        /// The version of the Secure Element firmware.
+        version: u32,
+        /// The upgrade blob.
+        data: &'data [u8],
+    },
+    /// Program data to be written to flash.
+    Program {
+        /// The flash address to write the data to.
+        flash_start_address: u32,
+        /// The program data.
+        data: &'data [u8],
+    },
+    /// Program data to be written to flash after erasing it.
+    EraseProgram {
+        /// The flash address to write the data to.
+        flash_start_address: u32,
+        /// The program data.
+        data: &'data [u8],
+    },
+    /// LZ4-compressed program data.
+    ProgramLz4 {
+        /// The flash address to write the decompressed data to.
+        flash_start_address: u32,
+        /// The compressed program data.
+        data: &'data [u8],
+    },
+    /// LZMA-compressed program data.
+    ProgramLzma {
+        /// The flash address to write the decompressed data to.
+        flash_start_address: u32,
+        /// The compressed program data.
+        data: &'data [u8],
+    },
+    /// Application-specific metadata.
+    Metadata(&'data [u8]),
+    /// Version dependencies of the image.
+    VersionDependency(&'data [u8]),
+    /// Marks the start of the encrypted part of the image.
+    EncryptionHeader(&'data [u8]),
+    /// Initialization data of the encryption.
+    EncryptionInit {
+        /// The length of the encrypted message.
+        message_length: u32,
+        /// The AES-CCM nonce.
+        nonce: [u8; 12],
+    },
+    /// Encrypted GBL tags.
+    EncryptedData(&'data [u8]),
+    /// The message authentication code of the encrypted data.
+    EncryptionMac(&'data [u8]),
+    /// An ECDSA-P256 signature over all preceding data.
+    SignatureEcdsaP256 {
+        /// The signature's `r` value.
+        r: [u8; 32],
+        /// The signature's `s` value.
+        s: [u8; 32],
+    },
+    /// An ECDSA-P256 certificate.
+    CertificateEcdsaP256(&'data [u8]),
+    /// The end tag containing the CRC32 checksum over all preceding data.
+    End {
+        /// The CRC32 checksum.
+        crc: u32,
+    },
+    /// A tag with an unknown tag ID.
+    Unknown {
+        /// The tag ID.
+        id: u32,
+        /// The tag's raw data.
+        data: &'data [u8],
+    },
+    /// A tag whose data does not match the layout of its tag ID.
+    Malformed {
+        /// The tag ID.
+        id: u32,
+        /// The tag's raw data.
+        data: &'data [u8],
+    },
+}
+
+impl<'data> Tag<'data> {
+    /// Decode a tag from its ID and data.
+    #[must_use]
+    pub fn decode(id: u32, data: &'data [u8]) -> Self {
+        match id {
+            HEADER_TAG_ID => decode_exact(data).map(Self::Header),
+            APPLICATION => decode_exact(data).map(Self::Application),
+            BOOTLOADER => split_u32(data).and_then(|(version, rest)| {
+                split_u32(rest).map(|(address, data)| Self::Bootloader {
+                    version,
+                    address,
+                    data,
+                })
+            }),
+            SE_UPGRADE => split_u32(data).and_then(|(blob_size, rest)| {
+                split_u32(rest).map(|(version, data)| Self::SeUpgrade {
+                    blob_size,
+                    version,
+                    data,
+                })
+            }),
+            PROG => split_u32(data).map(|(flash_start_address, data)| Self::Program {
+                flash_start_address,
+                data,
+            }),
+            ERASEPROG => split_u32(data).map(|(flash_start_address, data)| Self::EraseProgram {
+                flash_start_address,
+                data,
+            }),
+            PROG_LZ4 => split_u32(data).map(|(flash_start_address, data)| Self::ProgramLz4 {
+                flash_start_address,
+                data,
+            }),
+            PROG_LZMA => split_u32(data).map(|(flash_start_address, data)| Self::ProgramLzma {
+                flash_start_address,
+                data,
+            }),
+            METADATA => Some(Self::Metadata(data)),
+            VERSION_DEPENDENCY => Some(Self::VersionDependency(data)),
+            ENCRYPTION_HEADER => Some(Self::EncryptionHeader(data)),
+            ENCRYPTION_INIT => split_u32(data).and_then(|(message_length, nonce)| {
+                nonce.try_into().ok().map(|nonce| Self::EncryptionInit {
+                    message_length,
+                    nonce,
+                })
+            }),
+            ENCRYPTED_DATA => Some(Self::EncryptedData(data)),
+            ENCRYPTION_MAC => Some(Self::EncryptionMac(data)),
+            SIGNATURE_ECDSA_P256 => data.split_first_chunk().and_then(|(r, s)| {
+                s.try_into()
+                    .ok()
+                    .map(|s| Self::SignatureEcdsaP256 { r: *r, s })
+            }),
+            CERTIFICATE_ECDSA_P256 => Some(Self::CertificateEcdsaP256(data)),
+            END_TAG_ID => decode_exact(data).map(|crc| Self::End { crc }),
+            id => Some(Self::Unknown { id, data }),
+        }
+        .unwrap_or(Self::Malformed { id, data })
+    }
+
+    /// Return the tag ID.
+    #[must_use]
+    pub const fn id(&self) -> u32 {
+        match self {
+            Self::Header(_) => HEADER_TAG_ID,
+            Self::Application(_) => APPLICATION,
+            Self::Bootloader { .. } => BOOTLOADER,
+            Self::SeUpgrade { .. } => SE_UPGRADE,
+            Self::Program { .. } => PROG,
+            Self::EraseProgram { .. } => ERASEPROG,
+            Self::ProgramLz4 { .. } => PROG_LZ4,
+            Self::ProgramLzma { .. } => PROG_LZMA,
+            Self::Metadata(_) => METADATA,
+            Self::VersionDependency(_) => VERSION_DEPENDENCY,
+            Self::EncryptionHeader(_) => ENCRYPTION_HEADER,
+            Self::EncryptionInit { .. } => ENCRYPTION_INIT,
+            Self::EncryptedData(_) => ENCRYPTED_DATA,
+            Self::EncryptionMac(_) => ENCRYPTION_MAC,
+            Self::SignatureEcdsaP256 { .. } => SIGNATURE_ECDSA_P256,
+            Self::CertificateEcdsaP256(_) => CERTIFICATE_ECDSA_P256,
+            Self::End { .. } => END_TAG_ID,
+            Self::Unknown { id, .. } | Self::Malformed { id, .. } => *id,
+        }
+    }
+
+    /// Returns whether the tag's data does not match the layout of its tag ID.
+    #[must_use]
+    pub const fn is_malformed(&self) -> bool {
+        matches!(self, Self::Malformed { .. })
+    }
+}
+
+impl Display for Tag<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Header(header) => write!(f, "Header: {header}"),
+            Self::Application(application_info) => write!(f, "Application: {application_info}"),
+            Self::Bootloader {
+                version,
+                address,
+                data,
+            } => write!(
+                f,
+                "Bootloader: version {version:#010X}, address {address:#010X} ({} bytes)",
+                data.len()
+            ),
+            Self::SeUpgrade {
+                blob_size,
+                version,
+                data,
+            } => write!(
+                f,
+                "SE upgrade: version {version:#010X}, blob size {blob_size} ({} bytes)",
+                data.len()
+            ),
+            Self::Program {
+                flash_start_address,
+                data,
+            } => write!(
+                f,
+                "Program data: address {flash_start_address:#010X} ({} bytes)",
+                data.len()
+            ),
+            Self::EraseProgram {
+                flash_start_address,
+                data,
+            } => write!(
+                f,
+                "Erase and program data: address {flash_start_address:#010X} ({} bytes)",
+                data.len()
+            ),
+            Self::ProgramLz4 {
+                flash_start_address,
+                data,
+            } => write!(
+                f,
+                "LZ4 program data: address {flash_start_address:#010X} ({} bytes)",
+                data.len()
+            ),
+            Self::ProgramLzma {
+                flash_start_address,
+                data,
+            } => write!(
+                f,
+                "LZMA program data: address {flash_start_address:#010X} ({} bytes)",
+                data.len()
+            ),
+            Self::Metadata(data) => write!(f, "Metadata ({} bytes)", data.len()),
+            Self::VersionDependency(data) => {
+                write!(f, "Version dependency ({} bytes)", data.len())
+            }
+            Self::EncryptionHeader(data) => write!(f, "Encryption header ({} bytes)", data.len()),
+            Self::EncryptionInit {
+                message_length,
+                nonce,
+            } => write!(
+                f,
+                "Encryption init: message length {message_length}, nonce {}",
+                Hex(nonce)
+            ),
+            Self::EncryptedData(data) => write!(f, "Encrypted data ({} bytes)", data.len()),
+            Self::EncryptionMac(data) => write!(f, "Encryption MAC: {}", Hex(data)),
+            Self::SignatureEcdsaP256 { r, s } => {
+                write!(f, "ECDSA-P256 signature: r {}, s {}", Hex(r), Hex(s))
+            }
+            Self::CertificateEcdsaP256(data) => {
+                write!(f, "ECDSA-P256 certificate ({} bytes)", data.len())
+            }
+            Self::End { crc } => write!(f, "End: CRC32 {crc:#010X}"),
+            Self::Unknown { data, .. } => write!(f, "Unknown ({} bytes)", data.len()),
+            Self::Malformed { data, .. } => write!(f, "Malformed ({} bytes)", data.len()),
+        }
+    }
+}
+
+/// Split a little endian `u32` from the start of the data.
+fn split_u32(data: &[u8]) -> Option<(u32, &[u8])> {
+    data.split_first_chunk()
+        .map(|(value, rest)| (u32::from_le_bytes(*value), rest))
+}
+
+/// Decode a value that must span the entire data.
+fn decode_exact<T>(data: &[u8]) -> Option<T>
+where
+    T: FromLeStream,
+{
+    T::from_le_stream_exact(data.iter().copied()).ok()
+}
@@ -0,0 +1,25 @@
+use std::fmt::{Display, LowerHex};
+
+/// Displays a byte slice as contiguous upper case hex digits, or lower case ones using `{:x}`.
+pub struct Hex<'bytes>(pub &'bytes [u8]);
+
+impl Display for Hex<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{byte:02X}"))
+    }
+}
+
+impl LowerHex for Hex<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+    }
+}
+
+/// Serialize a byte slice as a string of contiguous upper case hex digits.
+#[cfg(feature = "serde")]
+pub fn serialize<S>(bytes: impl AsRef<[u8]>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.collect_str(&Hex(bytes.as_ref()))
+}
@@ -2,18 +2,44 @@
 
 pub use self::clear_buffer::ClearBuffer;
 pub use self::discard_callbacks::discard_callbacks;
+pub use self::firmware::{Firmware, FirmwareError};
 pub use self::flash_progress::FlashProgress;
-pub use self::fwupd::{FrameCount, Fwupd, Reset};
+#[cfg(feature = "clap")]
+pub use self::fwupd::XmodemArgs;
+pub use self::fwupd::{BlockSize, FrameCount, Fwupd, Reset, TransferError, XmodemConfig};
+pub use self::hex::Hex;
 pub use self::ignore_timeout::IgnoreTimeout;
 pub use self::make_uart::make_uart;
 pub use self::ota_file::OtaFile;
+pub use self::preflight::{Preflight, RunPreflight};
+pub use self::signature::{PublicKey, SignatureError};
+pub use self::uart_params::UartParams;
 
 mod clear_buffer;
+mod crc_reader;
 mod discard_callbacks;
+pub mod ebl;
+mod firmware;
 mod flash_progress;
 mod fwupd;
+pub mod gbl;
+mod hex;
 mod ignore_timeout;
 mod launch_bootloader;
 mod make_uart;
-mod ota_file;
+#[cfg_attr(
+    not(test),
+    deny(
+        clippy::arithmetic_side_effects,
+        clippy::expect_used,
+        clippy::indexing_slicing,
+        clippy::panic,
+        clippy::unreachable,
+        clippy::unwrap_used
+    )
+)]
+pub mod ota_file;
+mod preflight;
+mod signature;
+mod uart_params;
 mod xmodem;
@@ -1,36 +1,90 @@
+//! Zigbee OTA (Over-The-Air) upgrade files.
+
 use std::fmt::Display;
+use std::io::Read;
 use std::ops::RangeInclusive;
 
-use ezsp::ember::Eui64;
-use le_stream::FromLeStream;
+use le_stream::{FromLeStream, ToLeStream};
 
-use self::header::Header;
-use self::tag::Tag;
-use self::upgrade_file_destination::{ThreadId, UpgradeFileDestination};
+pub use self::builder::{BuildError, OtaFileBuilder};
+pub use self::description::Description;
+pub use self::error::{Field, OtaError};
+pub use self::header::{
+    FieldControl, FirmwareVersion, Header, HeaderDescription, HeaderVersion, ReleaseType,
+    StackVersion,
+};
+use self::integrity_code::IntegrityCode;
+use self::parser::Parser;
+pub use self::parser::Tags;
+pub use self::reader::{OtaReader, TagInfo, UpgradeImage};
+pub use self::registry::{Registry, RegistryError};
+pub use self::tag::Tag;
+pub use self::tag_kind::{
+    EcdsaCertificate163k1, EcdsaCertificate283k1, EcdsaSignature, EcdsaSignature163k1,
+    EcdsaSignature283k1, TagKind,
+};
+pub use self::upgrade_file_destination::{ThreadId, UpgradeFileDestination};
+pub use self::validation_error::ValidationError;
+use crate::signature::{CryptoSuite, PublicKey, SignatureError};
 
 const MAGIC: Magic = [0x1E, 0xF1, 0xEE, 0x0B];
-const HEADER_VERSION_ZIGBEE: u16 = 0x0100;
-const HEADER_VERSION_THREAD: u16 = 0x0200;
+const SIGNER_SIZE: usize = 8;
 
 type Magic = [u8; 4];
 
+mod aes_mmo;
+mod builder;
+mod description;
+mod error;
 mod header;
+mod integrity_code;
+mod parser;
+mod reader;
+mod registry;
 mod tag;
+mod tag_kind;
 mod upgrade_file_destination;
+mod validation_error;
 
 /// Represents an OTA (Over-The-Air) file used for firmware updates.
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct OtaFile {
+    #[cfg_attr(feature = "serde", serde(serialize_with = "crate::hex::serialize"))]
     magic: Magic,
     header: Header,
     security_credentials: Option<u8>,
     upgrade_file_destination: Option<UpgradeFileDestination>,
     hardware_versions: Option<RangeInclusive<u16>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            serialize_with = "crate::hex::serialize",
+            skip_serializing_if = "Vec::is_empty"
+        )
+    )]
+    header_extension: Vec<u8>,
     tags: Vec<Tag>,
-    payload: Vec<u8>,
 }
 
 impl OtaFile {
+    /// Length of the magic and the header's fixed fields in bytes.
+    pub const MIN_HEADER_LENGTH: u16 = 4 + Header::SIZE;
+
+    /// Read and parse an OTA file from the given reader.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`OtaError`] if reading fails or the data is not a valid OTA file.
+    pub fn from_reader<R>(mut reader: R) -> Result<Self, OtaError>
+    where
+        R: Read,
+    {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        Self::try_from(bytes.as_slice())
+    }
+
     /// Return the OTA file's header magic.
     #[must_use]
     pub const fn magic(&self) -> &Magic {
@@ -63,94 +117,626 @@ impl OtaFile {
         self.hardware_versions.as_ref()
     }
 
+    /// Return the header fields following the known fields, if any.
+    ///
+    /// These fields are unknown to this implementation and are preserved as is.
+    #[must_use]
+    pub fn header_extension(&self) -> &[u8] {
+        &self.header_extension
+    }
+
     /// Return the OTA file's tags.
     #[must_use]
     pub fn tags(&self) -> &[Tag] {
         &self.tags
     }
 
-    /// Return the OTA file's payload.
+    /// Return the OTA file's payload, i.e. the data of the upgrade image tag, if present.
     #[must_use]
-    pub fn payload(&self) -> &[u8] {
-        &self.payload
+    pub fn payload(&self) -> Option<&[u8]> {
+        self.upgrade_image().map(Tag::data)
     }
 
-    /// Validate the OTA file's magic number.
+    /// Validate the OTA file's magic number and, if present, its image integrity code.
+    ///
+    /// The image integrity code is an AES-MMO hash if it is 16 bytes long and a SHA-256 hash if it is 32 bytes long.
+    /// It is calculated over the entire file up to, but excluding, the image integrity code's value.
     ///
     /// # Returns
     ///
-    /// If the magic number matches, returns `Ok(Self)`.
+    /// If the OTA file is valid, returns `Ok(Self)`.
     ///
     /// # Errors
     ///
-    /// If the magic number does not match, returns `Err(Magic)` with the faulty magic number.
-    pub fn validate(self) -> Result<Self, Magic> {
-        if self.magic == MAGIC {
-            Ok(self)
-        } else {
-            Err(self.magic)
+    /// Returns a [`ValidationError`] if the magic number or the image integrity code does not match.
+    pub fn validate(self) -> Result<Self, ValidationError> {
+        if self.magic != MAGIC {
+            return Err(ValidationError::InvalidMagic(self.magic));
         }
+
+        self.verify_integrity_code()?;
+        Ok(self)
+    }
+
+    /// Verify the OTA file's ECDSA signature sub-element against the given public key.
+    ///
+    /// The signature is calculated over the AES-MMO hash of the entire file up to and including the signer's IEEE address.
+    /// If the file contains a signing certificate for the signer, the given key is considered to be the
+    /// certificate authority's key, from which the signer's key is reconstructed.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`SignatureError`] if the file is not signed or the signature does not match.
+    pub fn verify_signature(&self, key: &PublicKey) -> Result<(), SignatureError> {
+        let (index, tag, crypto_suite, signer) = self
+            .tags
+            .iter()
+            .enumerate()
+            .find_map(|(index, tag)| match tag.kind()? {
+                TagKind::EcdsaSignature163k1(signature) => {
+                    Some((index, tag, CryptoSuite::Sect163k1, signature.signer()))
+                }
+                TagKind::EcdsaSignature283k1(signature) => {
+                    Some((index, tag, CryptoSuite::Sect283k1, signature.signer()))
+                }
+                _ => None,
+            })
+            .ok_or(SignatureError::Unsigned)?;
+
+        let certificate = self.tags.iter().find_map(|certificate| {
+            let reconstruction_data = match (crypto_suite, certificate.kind()?) {
+                (CryptoSuite::Sect163k1, TagKind::EcdsaSigningCertificate163k1(certificate))
+                    if certificate.subject() == signer =>
+                {
+                    certificate.public_key_reconstruction_data().to_vec()
+                }
+                (CryptoSuite::Sect283k1, TagKind::EcdsaSigningCertificate283k1(certificate))
+                    if certificate.subject() == signer =>
+                {
+                    certificate.public_key().to_vec()
+                }
+                _ => return None,
+            };
+            Some((reconstruction_data, aes_mmo::hash(certificate.data())))
+        });
+
+        let (signer, signature) = tag
+            .data()
+            .split_at_checked(SIGNER_SIZE)
+            .ok_or(SignatureError::Unsigned)?;
+        let mut bytes = self.bytes_until(index);
+        bytes.extend_from_slice(signer);
+
+        key.verify_zigbee(
+            crypto_suite,
+            &aes_mmo::hash(&bytes),
+            signature,
+            certificate
+                .as_ref()
+                .map(|(reconstruction_data, hash)| (reconstruction_data.as_slice(), &hash[..])),
+        )
+    }
+
+    /// Return a description of the OTA file that resolves names through the given registry.
+    #[must_use]
+    pub const fn describe<'ota>(&'ota self, registry: &'ota Registry) -> Description<'ota> {
+        Description::new(self, registry)
     }
 
-    /// Convert the OTA file into a payload vector.
+    /// Convert the OTA file into a payload vector, if an upgrade image tag is present.
     #[must_use]
-    pub fn into_payload(self) -> Vec<u8> {
-        self.payload
+    pub fn into_payload(self) -> Option<Vec<u8>> {
+        self.tags
+            .into_iter()
+            .find(|tag| tag.id() == Tag::UPGRADE_IMAGE)
+            .map(Tag::into_data)
+    }
+
+    /// Return the upgrade image tag, if present.
+    fn upgrade_image(&self) -> Option<&Tag> {
+        self.tags.iter().find(|tag| tag.id() == Tag::UPGRADE_IMAGE)
+    }
+
+    /// Serialize the OTA file up to and including the ID and length of the tag at the given index.
+    fn bytes_until(&self, index: usize) -> Vec<u8> {
+        let mut bytes: Vec<u8> = Self {
+            magic: self.magic,
+            header: self.header.clone(),
+            security_credentials: self.security_credentials,
+            upgrade_file_destination: self.upgrade_file_destination.clone(),
+            hardware_versions: self.hardware_versions.clone(),
+            header_extension: self.header_extension.clone(),
+            tags: self.tags.get(..index).unwrap_or_default().to_vec(),
+        }
+        .to_le_stream()
+        .collect();
+
+        if let Some(tag) = self.tags.get(index) {
+            bytes.extend(tag.id().to_le_stream());
+            bytes.extend(tag.length().to_le_stream());
+        }
+
+        bytes
+    }
+
+    /// Verify the image integrity code against the hash of the preceding data, if present.
+    fn verify_integrity_code(&self) -> Result<(), ValidationError> {
+        let Some((index, tag)) = self
+            .tags
+            .iter()
+            .enumerate()
+            .find(|(_, tag)| tag.id() == Tag::IMAGE_INTEGRITY_CODE)
+        else {
+            return Ok(());
+        };
+
+        let (mut integrity_code, expected) = IntegrityCode::for_tag(tag)?;
+        integrity_code.update(&self.bytes_until(index));
+        integrity_code.verify(expected)
     }
 }
 
 impl Display for OtaFile {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.header.fmt(f)
+        self.describe(Registry::builtin()).fmt(f)
+    }
+}
+
+impl ToLeStream for OtaFile {
+    type Iter = std::vec::IntoIter<u8>;
+
+    fn to_le_stream(self) -> Self::Iter {
+        let mut bytes = Vec::new();
+        bytes.extend(self.magic);
+        bytes.extend(self.header.to_le_stream());
+        bytes.extend(self.security_credentials);
+        bytes.extend(
+            self.upgrade_file_destination
+                .into_iter()
+                .flat_map(ToLeStream::to_le_stream),
+        );
+
+        if let Some(hardware_versions) = self.hardware_versions {
+            let (min, max) = hardware_versions.into_inner();
+            bytes.extend(min.to_le_stream());
+            bytes.extend(max.to_le_stream());
+        }
+
+        bytes.extend(self.header_extension);
+
+        bytes.extend(self.tags.into_iter().flat_map(ToLeStream::to_le_stream));
+        bytes.into_iter()
     }
 }
 
 impl FromLeStream for OtaFile {
-    fn from_le_stream<T>(mut bytes: T) -> Option<Self>
+    fn from_le_stream<T>(bytes: T) -> Option<Self>
     where
         T: Iterator<Item = u8>,
     {
-        let magic = Magic::from_le_stream(&mut bytes)?;
-        let header = Header::from_le_stream(&mut bytes)?;
-        let field_control = header.field_control();
-
-        let security_credentials = if field_control.has_security_credentials() {
-            Some(u8::from_le_stream(&mut bytes)?)
-        } else {
-            None
-        };
+        Parser::new(bytes, None).parse().ok()
+    }
+}
 
-        let upgrade_file_destination = if field_control.has_upgrade_file_destination() {
-            match header.version() {
-                HEADER_VERSION_ZIGBEE => Some(UpgradeFileDestination::Zigbee(
-                    Eui64::from_le_stream(&mut bytes)?,
-                )),
-                HEADER_VERSION_THREAD => Some(UpgradeFileDestination::Thread(
-                    ThreadId::from_le_stream(&mut bytes)?.into(),
-                )),
-                _ => None,
+impl TryFrom<&[u8]> for OtaFile {
+    type Error = OtaError;
+
+    /// Parse an OTA file, checking that its total image size matches the size of the data.
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Parser::new(bytes.iter().copied(), Some(bytes.len())).parse()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::ops::RangeInclusive;
+
+    use ezsp::ember::Eui64;
+    use le_stream::{FromLeStream, ToLeStream};
+    use sha2::{Digest, Sha256};
+
+    use super::{
+        BuildError, Field, HeaderVersion, OtaError, OtaFile, OtaFileBuilder, Parser, Tag, Tags,
+        UpgradeFileDestination, ValidationError, aes_mmo,
+    };
+    use crate::signature::tests::sign_sect163k1;
+    use crate::signature::{PublicKey, SignatureError};
+
+    const HEADER_LENGTH: u16 = 56;
+
+    /// Build a minimal OTA file with the given tags.
+    pub fn ota_file(tags: &[(u16, &[u8])]) -> Vec<u8> {
+        let tags_size: usize = tags.iter().map(|(_, data)| 6 + data.len()).sum();
+        let image_size = u32::from(HEADER_LENGTH) + u32::try_from(tags_size).unwrap();
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&[0x1E, 0xF1, 0xEE, 0x0B]);
+        bytes.extend_from_slice(&0x0100_u16.to_le_bytes());
+        bytes.extend_from_slice(&HEADER_LENGTH.to_le_bytes());
+        bytes.extend_from_slice(&0x0000_u16.to_le_bytes());
+        bytes.extend_from_slice(&0x1002_u16.to_le_bytes());
+        bytes.extend_from_slice(&0x0001_u16.to_le_bytes());
+        bytes.extend_from_slice(&0x0000_0001_u32.to_le_bytes());
+        bytes.extend_from_slice(&0x0002_u16.to_le_bytes());
+        bytes.extend_from_slice(&[0; 32]);
+        bytes.extend_from_slice(&image_size.to_le_bytes());
+
+        for (id, data) in tags {
+            bytes.extend_from_slice(&id.to_le_bytes());
+            bytes.extend_from_slice(&u32::try_from(data.len()).unwrap().to_le_bytes());
+            bytes.extend_from_slice(data);
+        }
+
+        bytes
+    }
+
+    #[test]
+    fn test_single_tag() {
+        let bytes = ota_file(&[(0x0000, &[0xEB, 0x17, 0xA6, 0x03])]);
+        let ota_file = OtaFile::from_le_stream_exact(bytes.into_iter()).unwrap();
+        assert_eq!(ota_file.tags().len(), 1);
+        assert_eq!(
+            ota_file.payload(),
+            Some([0xEB, 0x17, 0xA6, 0x03].as_slice())
+        );
+    }
+
+    #[test]
+    fn test_multiple_tags() {
+        let bytes = ota_file(&[
+            (0x0002, &[0xCE; 48]),
+            (0x0000, &[0xEB, 0x17, 0xA6, 0x03]),
+            (0x0001, &[0x5A; 50]),
+            (0x0003, &[0x11; 16]),
+        ]);
+        let ota_file = OtaFile::from_le_stream_exact(bytes.into_iter()).unwrap();
+        let ids: Vec<u16> = ota_file.tags().iter().map(Tag::id).collect();
+        assert_eq!(ids, [0x0002, 0x0000, 0x0001, 0x0003]);
+        assert_eq!(ota_file.tags()[2].data(), [0x5A; 50]);
+        assert_eq!(
+            ota_file.payload(),
+            Some([0xEB, 0x17, 0xA6, 0x03].as_slice())
+        );
+    }
+
+    #[test]
+    fn test_missing_upgrade_image() {
+        let bytes = ota_file(&[(0xF000, &[0x01, 0x02])]);
+        let ota_file = OtaFile::from_le_stream_exact(bytes.into_iter()).unwrap();
+        assert_eq!(ota_file.payload(), None);
+    }
+
+    #[test]
+    fn test_truncated_tag() {
+        let mut bytes = ota_file(&[(0x0000, &[0xEB, 0x17, 0xA6, 0x03])]);
+        bytes.pop();
+        assert!(OtaFile::from_le_stream_exact(bytes.into_iter()).is_err());
+    }
+
+    #[test]
+    fn test_try_from_slice() {
+        let bytes = ota_file(&[(0x0000, &[0xEB, 0x17, 0xA6, 0x03])]);
+        let ota_file = OtaFile::try_from(bytes.as_slice()).unwrap();
+        assert_eq!(
+            ota_file.payload(),
+            Some([0xEB, 0x17, 0xA6, 0x03].as_slice())
+        );
+        assert!(OtaFile::from_reader(bytes.as_slice()).is_ok());
+    }
+
+    #[test]
+    fn test_error_truncated_header() {
+        let bytes = ota_file(&[]);
+        let error = OtaFile::try_from(&bytes[..30]).unwrap_err();
+        assert!(matches!(
+            error,
+            OtaError::Truncated {
+                offset: 20,
+                field: Field::HeaderString
             }
-        } else {
-            None
-        };
+        ));
+        assert_eq!(error.offset(), Some(20));
+    }
 
-        let hardware_versions = if field_control.has_hardware_version() {
-            Some(RangeInclusive::<u16>::from_le_stream(&mut bytes)?)
-        } else {
-            None
-        };
+    #[test]
+    fn test_error_invalid_magic() {
+        let mut bytes = ota_file(&[]);
+        bytes[3] = 0x00;
+        assert!(matches!(
+            OtaFile::try_from(bytes.as_slice()),
+            Err(OtaError::InvalidMagic([0x1E, 0xF1, 0xEE, 0x00]))
+        ));
+    }
+
+    #[test]
+    fn test_error_unknown_header_version() {
+        let mut bytes = ota_file(&[]);
+        bytes[5] = 0x03;
+        let error = OtaFile::try_from(bytes.as_slice()).unwrap_err();
+        assert!(matches!(error, OtaError::UnknownHeaderVersion(0x0300)));
+        assert_eq!(error.field(), Some(Field::HeaderVersion));
+    }
+
+    #[test]
+    fn test_error_header_too_short() {
+        let mut bytes = ota_file(&[]);
+        bytes[6] = 40;
+        assert!(matches!(
+            OtaFile::try_from(bytes.as_slice()),
+            Err(OtaError::HeaderTooShort(40))
+        ));
+    }
+
+    #[test]
+    fn test_error_header_length_mismatch() {
+        let mut bytes = ota_file(&[(0x0000, &[0xEB, 0x17, 0xA6, 0x03])]);
+        bytes[8] = 0x04;
+        assert!(matches!(
+            OtaFile::try_from(bytes.as_slice()),
+            Err(OtaError::HeaderLengthMismatch {
+                length: 56,
+                expected: 60
+            })
+        ));
+    }
+
+    #[test]
+    fn test_header_extension() {
+        let mut bytes = ota_file(&[(0x0000, &[0xEB, 0x17, 0xA6, 0x03])]);
+        bytes[6] = 58;
+        bytes[52] += 2;
+        bytes.splice(56..56, [0xAA, 0xBB]);
+        let ota_file = OtaFile::try_from(bytes.as_slice()).unwrap();
+        assert_eq!(ota_file.header_extension(), [0xAA, 0xBB]);
+        assert_eq!(ota_file.payload(), Some(&[0xEB, 0x17, 0xA6, 0x03][..]));
+        assert_eq!(ota_file.to_le_stream().collect::<Vec<_>>(), bytes);
+    }
+
+    #[test]
+    fn test_error_image_size_mismatch() {
+        let mut bytes = ota_file(&[(0x0000, &[0xEB, 0x17, 0xA6, 0x03])]);
+        bytes.push(0x00);
+        assert!(matches!(
+            OtaFile::try_from(bytes.as_slice()),
+            Err(OtaError::ImageSizeMismatch {
+                image_size: 66,
+                file_size: 67
+            })
+        ));
+    }
+
+    #[test]
+    fn test_error_tag_overrun() {
+        let mut bytes = ota_file(&[(0x0000, &[0xEB, 0x17, 0xA6, 0x03])]);
+        bytes[58] = 5;
+        assert!(matches!(
+            OtaFile::try_from(bytes.as_slice()),
+            Err(OtaError::TagOverrun {
+                offset: 56,
+                id: 0x0000,
+                length: 5,
+                remaining: 10
+            })
+        ));
+    }
+
+    #[test]
+    fn test_error_image_size_too_small() {
+        let mut bytes = ota_file(&[]);
+        bytes[52..56].copy_from_slice(&40_u32.to_le_bytes());
+        assert!(matches!(
+            Parser::new(bytes.into_iter(), None).parse(),
+            Err(OtaError::ImageSizeTooSmall {
+                image_size: 40,
+                header_length: 56
+            })
+        ));
+    }
+
+    #[test]
+    fn test_tags() {
+        let bytes = [
+            0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0xAB, 0xCD, 0x05, 0x00, 0x08, 0x00, 0x00, 0x00,
+        ];
+        let mut tags = Tags::new(bytes.into_iter(), 14);
+        assert_eq!(tags.next().unwrap().unwrap().data(), &[0xAB, 0xCD]);
+        assert!(matches!(
+            tags.next(),
+            Some(Err(OtaError::TagOverrun {
+                offset: 8,
+                id: 0x0005,
+                length: 8,
+                remaining: 6
+            }))
+        ));
+        assert!(tags.next().is_none());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let bytes = ota_file(&[
+            (0x0000, &[0xEB, 0x17, 0xA6, 0x03]),
+            (0x0001, &[0x5A; 50]),
+            (0x0003, &[0x11; 16]),
+        ]);
+        let ota_file = OtaFile::from_le_stream_exact(bytes.iter().copied()).unwrap();
+        assert_eq!(ota_file.to_le_stream().collect::<Vec<_>>(), bytes);
+    }
+
+    /// Build an OTA file with an upgrade image and an image integrity code of the given size calculated by `hash`.
+    pub fn ota_file_with_integrity_code(hash: fn(&[u8]) -> Vec<u8>, size: usize) -> Vec<u8> {
+        let mut bytes = ota_file(&[
+            (0x0000, &[0x01, 0x02, 0x03, 0x04]),
+            (0x0003, &vec![0; size]),
+        ]);
+        let code = bytes.len() - size;
+        let calculated = hash(&bytes[..code]);
+        bytes[code..].copy_from_slice(&calculated);
+        bytes
+    }
+
+    #[test]
+    fn test_integrity_code_aes_mmo() {
+        let bytes = ota_file_with_integrity_code(|data| aes_mmo::hash(data).to_vec(), 16);
+        let ota_file = OtaFile::from_le_stream_exact(bytes.into_iter()).unwrap();
+        assert!(ota_file.validate().is_ok());
+    }
+
+    #[test]
+    fn test_integrity_code_sha256() {
+        let bytes = ota_file_with_integrity_code(|data| Sha256::digest(data).to_vec(), 32);
+        let ota_file = OtaFile::from_le_stream_exact(bytes.into_iter()).unwrap();
+        assert!(ota_file.validate().is_ok());
+    }
+
+    #[test]
+    fn test_integrity_code_mismatch() {
+        let mut bytes = ota_file_with_integrity_code(|data| aes_mmo::hash(data).to_vec(), 16);
+        bytes[62] ^= 0xFF;
+        let ota_file = OtaFile::from_le_stream_exact(bytes.into_iter()).unwrap();
+        assert!(matches!(
+            ota_file.validate(),
+            Err(ValidationError::IntegrityCodeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_integrity_code() {
+        let bytes = ota_file(&[(0x0000, &[0xEB, 0x17, 0xA6, 0x03]), (0x0003, &[0; 20])]);
+        let ota_file = OtaFile::from_le_stream_exact(bytes.into_iter()).unwrap();
+        assert_eq!(
+            ota_file.validate(),
+            Err(ValidationError::InvalidIntegrityCode(20))
+        );
+    }
+
+    /// Build an OTA file signed with the sect163k1 test key and return it along with the public key.
+    fn signed_ota_file() -> (Vec<u8>, Vec<u8>) {
+        let mut signature = vec![0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01];
+        signature.extend_from_slice(&[0; 42]);
+        let mut bytes = ota_file(&[(0x0000, &[0xEB, 0x17, 0xA6, 0x03]), (0x0001, &signature)]);
+        let start = bytes.len() - 42;
+        let (public_key, signature) = sign_sect163k1(&aes_mmo::hash(&bytes[..start]));
+        bytes[start..].copy_from_slice(&signature);
+        (bytes, public_key)
+    }
+
+    #[test]
+    fn test_verify_signature() {
+        let (bytes, public_key) = signed_ota_file();
+        let ota_file = OtaFile::from_le_stream_exact(bytes.into_iter()).unwrap();
+        let public_key = PublicKey::from_bytes(&public_key).unwrap();
+        assert_eq!(ota_file.verify_signature(&public_key), Ok(()));
+    }
+
+    #[test]
+    fn test_verify_signature_mismatch() {
+        let (mut bytes, public_key) = signed_ota_file();
+        bytes[62] ^= 0xFF;
+        let ota_file = OtaFile::from_le_stream_exact(bytes.into_iter()).unwrap();
+        let public_key = PublicKey::from_bytes(&public_key).unwrap();
+        assert_eq!(
+            ota_file.verify_signature(&public_key),
+            Err(SignatureError::Mismatch)
+        );
+    }
+
+    #[test]
+    fn test_verify_unsigned() {
+        let (_, public_key) = signed_ota_file();
+        let bytes = ota_file(&[(0x0000, &[0xEB, 0x17, 0xA6, 0x03])]);
+        let ota_file = OtaFile::from_le_stream_exact(bytes.into_iter()).unwrap();
+        let public_key = PublicKey::from_bytes(&public_key).unwrap();
+        assert_eq!(
+            ota_file.verify_signature(&public_key),
+            Err(SignatureError::Unsigned)
+        );
+    }
+
+    #[test]
+    fn test_builder() {
+        let ota_file = OtaFileBuilder::new(0x1002, 0x0001, 0x0000_0001)
+            .zigbee_stack_version(0x0002)
+            .security_credentials(0x01)
+            .upgrade_file_destination(UpgradeFileDestination::Zigbee(Eui64::from([
+                0x00, 0x0D, 0x6F, 0x00, 0x01, 0x02, 0x03, 0x04,
+            ])))
+            .hardware_versions(0x0001..=0x0003)
+            .name("test")
+            .tag(Tag::new(0x0000, vec![0xEB, 0x17, 0xA6, 0x03]).unwrap())
+            .build()
+            .unwrap();
+        let header = ota_file.header();
+        assert_eq!(header.version(), 0x0100);
+        assert_eq!(header.length(), 56 + 1 + 8 + 4);
+        assert_eq!(header.image_size(), 56 + 1 + 8 + 4 + 6 + 4);
+        assert!(header.field_control().has_security_credentials());
+        assert!(header.field_control().has_upgrade_file_destination());
+        assert!(header.field_control().has_hardware_version());
+
+        let bytes: Vec<u8> = ota_file.clone().to_le_stream().collect();
+        assert_eq!(bytes.len(), 56 + 1 + 8 + 4 + 6 + 4);
+        assert_eq!(
+            OtaFile::from_le_stream_exact(bytes.into_iter()).unwrap(),
+            ota_file
+        );
+    }
+
+    #[test]
+    fn test_builder_name_too_long() {
+        assert_eq!(
+            OtaFileBuilder::new(0x1002, 0x0001, 0x0000_0001)
+                .name([b'x'; 33])
+                .build(),
+            Err(BuildError::NameTooLong(33))
+        );
+    }
+
+    #[test]
+    fn test_builder_empty_hardware_versions() {
+        assert_eq!(
+            OtaFileBuilder::new(0x1002, 0x0001, 0x0000_0001)
+                .hardware_versions(RangeInclusive::new(0x0003, 0x0001))
+                .build(),
+            Err(BuildError::EmptyHardwareVersions)
+        );
+    }
+
+    #[test]
+    fn test_thread() {
+        let thread_id = core::array::from_fn(|index| u8::try_from(index).unwrap());
+        let ota_file = OtaFileBuilder::new(0x1002, 0x0001, 0x0000_0001)
+            .upgrade_file_destination(UpgradeFileDestination::Thread(Box::new(thread_id)))
+            .tag(Tag::new(0x0000, vec![0xEB, 0x17, 0xA6, 0x03]).unwrap())
+            .build()
+            .unwrap();
+        assert_eq!(
+            ota_file.header().header_version(),
+            Some(HeaderVersion::Thread)
+        );
+        assert_eq!(ota_file.header().length(), 56 + 32);
+
+        let bytes: Vec<u8> = ota_file.clone().to_le_stream().collect();
+        assert_eq!(bytes[4..6], [0x00, 0x02]);
+        let parsed = OtaFile::try_from(bytes.as_slice()).unwrap();
+        assert_eq!(parsed, ota_file);
+        assert_eq!(
+            parsed.upgrade_file_destination(),
+            Some(&UpgradeFileDestination::Thread(Box::new(thread_id)))
+        );
+        assert!(parsed.to_string().contains(
+            "OTA destination:   Thread: \
+             000102030405060708090A0B0C0D0E0F101112131415161718191A1B1C1D1E1F"
+        ));
+    }
 
-        let tags = header.parse_tags(&mut bytes);
-        let payload = bytes.collect();
-
-        Some(Self {
-            magic,
-            header,
-            security_credentials,
-            upgrade_file_destination,
-            hardware_versions,
-            tags,
-            payload,
-        })
+    #[test]
+    fn test_builder_destination_mismatch() {
+        assert_eq!(
+            OtaFileBuilder::new(0x1002, 0x0001, 0x0000_0001)
+                .header_version(HeaderVersion::Thread)
+                .upgrade_file_destination(UpgradeFileDestination::Zigbee(Eui64::from([0; 8])))
+                .build(),
+            Err(BuildError::DestinationMismatch)
+        );
     }
 }
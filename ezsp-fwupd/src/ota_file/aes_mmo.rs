@@ -0,0 +1,125 @@
+//! The AES-MMO hash as specified in annex B.6 of the Zigbee specification.
+
+use aes::cipher::{BlockEncrypt, KeyInit};
+use aes::{Aes128, Block};
+
+/// Size of an AES-MMO hash in bytes.
+pub const SIZE: usize = 16;
+
+/// Calculate the Matyas-Meyer-Oseas hash of the given data using AES-128.
+pub fn hash(data: &[u8]) -> [u8; SIZE] {
+    let mut hasher = AesMmo::default();
+    hasher.update(data);
+    hasher.finalize()
+}
+
+/// Incremental calculation of the Matyas-Meyer-Oseas hash.
+#[derive(Clone, Debug, Default)]
+pub struct AesMmo {
+    hash: [u8; SIZE],
+    pending: Vec<u8>,
+    length: usize,
+}
+
+impl AesMmo {
+    /// Feed the given data into the hash.
+    pub fn update(&mut self, data: &[u8]) {
+        self.length = self.length.saturating_add(data.len());
+        self.pending.extend_from_slice(data);
+        let mut blocks = self.pending.chunks_exact(SIZE);
+
+        for block in &mut blocks {
+            self.hash = compress(self.hash, block.try_into().unwrap_or_default());
+        }
+
+        let processed = self.pending.len().saturating_sub(blocks.remainder().len());
+        self.pending.drain(..processed);
+    }
+
+    /// Pad the remaining data and return the hash.
+    pub fn finalize(self) -> [u8; SIZE] {
+        pad(&self.pending, self.length)
+            .chunks_exact(SIZE)
+            .fold(self.hash, |hash, block| {
+                compress(hash, block.try_into().unwrap_or_default())
+            })
+    }
+}
+
+/// Encrypt the block with the previous hash as key and XOR the result with the block.
+fn compress(hash: [u8; SIZE], block: [u8; SIZE]) -> [u8; SIZE] {
+    let mut encrypted = Block::from(block);
+    Aes128::new(&hash.into()).encrypt_block(&mut encrypted);
+    let mut result: [u8; SIZE] = encrypted.into();
+    result
+        .iter_mut()
+        .zip(block)
+        .for_each(|(byte, plain)| *byte ^= plain);
+    result
+}
+
+/// Pad the last incomplete block with a one bit, zeros and the message length in bits.
+///
+/// Messages shorter than 2^16 bits encode their length in two bytes.
+/// Longer messages encode it in four bytes, followed by two zero bytes.
+fn pad(remainder: &[u8], length: usize) -> Vec<u8> {
+    let bits = u64::try_from(length).unwrap_or(u64::MAX).wrapping_mul(8);
+    let mut trailer = Vec::with_capacity(6);
+
+    if bits < 1 << 16 {
+        trailer.extend_from_slice(&bits.to_be_bytes()[6..]);
+    } else {
+        trailer.extend_from_slice(&bits.to_be_bytes()[4..]);
+        trailer.extend_from_slice(&[0, 0]);
+    }
+
+    let mut padded = remainder.to_vec();
+    padded.push(0x80);
+
+    while !padded
+        .len()
+        .wrapping_add(trailer.len())
+        .is_multiple_of(SIZE)
+    {
+        padded.push(0x00);
+    }
+
+    padded.extend(trailer);
+    padded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AesMmo, hash};
+
+    #[test]
+    fn test_single_byte() {
+        assert_eq!(
+            hash(&[0xC0]),
+            [
+                0xAE, 0x3A, 0x10, 0x2A, 0x28, 0xD4, 0x3E, 0xE0, 0xD4, 0xA0, 0x9E, 0x22, 0x78, 0x8B,
+                0x20, 0x6C
+            ]
+        );
+    }
+
+    #[test]
+    fn test_single_block() {
+        let data: Vec<u8> = (0xC0..=0xCF).collect();
+        assert_eq!(
+            hash(&data),
+            [
+                0xA7, 0x97, 0x7E, 0x88, 0xBC, 0x0B, 0x61, 0xE8, 0x21, 0x08, 0x27, 0x10, 0x9A, 0x22,
+                0x8F, 0x2D
+            ]
+        );
+    }
+
+    #[test]
+    fn test_incremental() {
+        let data: Vec<u8> = (0..=0xFF).collect();
+        let mut hasher = AesMmo::default();
+        data.chunks(7).for_each(|chunk| hasher.update(chunk));
+        assert_eq!(hasher.finalize(), hash(&data));
+    }
+}
@@ -0,0 +1,189 @@
+use std::ops::RangeInclusive;
+
+pub use self::error::BuildError;
+use super::header::HEADER_STRING_LENGTH;
+use super::{FieldControl, Header, HeaderVersion, MAGIC, OtaFile, Tag, UpgradeFileDestination};
+
+mod error;
+
+/// Builder for [`OtaFile`]s.
+///
+/// The header's length, image size and field control flags are derived from the fields that have been set.
+/// Unless set explicitly, the header version is derived from the upgrade file destination
+/// and defaults to [`HeaderVersion::Zigbee`].
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct OtaFileBuilder {
+    header_version: Option<HeaderVersion>,
+    manufacturer_id: u16,
+    image_type: u16,
+    firmware_version: u32,
+    zigbee_stack_version: u16,
+    name: Vec<u8>,
+    security_credentials: Option<u8>,
+    upgrade_file_destination: Option<UpgradeFileDestination>,
+    hardware_versions: Option<RangeInclusive<u16>>,
+    tags: Vec<Tag>,
+}
+
+impl OtaFileBuilder {
+    /// Create a new builder for an OTA file with the given manufacturer ID, image type and firmware version.
+    #[must_use]
+    pub const fn new(manufacturer_id: u16, image_type: u16, firmware_version: u32) -> Self {
+        Self {
+            header_version: None,
+            manufacturer_id,
+            image_type,
+            firmware_version,
+            zigbee_stack_version: 0,
+            name: Vec::new(),
+            security_credentials: None,
+            upgrade_file_destination: None,
+            hardware_versions: None,
+            tags: Vec::new(),
+        }
+    }
+
+    /// Set the header version.
+    #[must_use]
+    pub const fn header_version(mut self, header_version: HeaderVersion) -> Self {
+        self.header_version = Some(header_version);
+        self
+    }
+
+    /// Set the Zigbee stack version.
+    #[must_use]
+    pub const fn zigbee_stack_version(mut self, zigbee_stack_version: u16) -> Self {
+        self.zigbee_stack_version = zigbee_stack_version;
+        self
+    }
+
+    /// Set the name, i.e. the header string, of the OTA file.
+    #[must_use]
+    pub fn name(mut self, name: impl Into<Vec<u8>>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Set the security credentials version.
+    #[must_use]
+    pub const fn security_credentials(mut self, security_credentials: u8) -> Self {
+        self.security_credentials = Some(security_credentials);
+        self
+    }
+
+    /// Set the upgrade file destination, making the OTA file device-specific.
+    #[must_use]
+    pub fn upgrade_file_destination(
+        mut self,
+        upgrade_file_destination: UpgradeFileDestination,
+    ) -> Self {
+        self.upgrade_file_destination = Some(upgrade_file_destination);
+        self
+    }
+
+    /// Set the supported hardware versions.
+    #[must_use]
+    pub const fn hardware_versions(mut self, hardware_versions: RangeInclusive<u16>) -> Self {
+        self.hardware_versions = Some(hardware_versions);
+        self
+    }
+
+    /// Append a tag.
+    #[must_use]
+    pub fn tag(mut self, tag: Tag) -> Self {
+        self.tags.push(tag);
+        self
+    }
+
+    /// Append multiple tags.
+    #[must_use]
+    pub fn tags(mut self, tags: impl IntoIterator<Item = Tag>) -> Self {
+        self.tags.extend(tags);
+        self
+    }
+
+    /// Build the OTA file.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`BuildError`] if the name is too long, if the upgrade file destination does not match
+    /// the header version, if the range of hardware versions is empty
+    /// or if the image size exceeds the supported maximum.
+    pub fn build(self) -> Result<OtaFile, BuildError> {
+        let version = self
+            .header_version
+            .or_else(|| {
+                self.upgrade_file_destination
+                    .as_ref()
+                    .map(UpgradeFileDestination::header_version)
+            })
+            .unwrap_or(HeaderVersion::Zigbee);
+
+        if let Some(destination) = &self.upgrade_file_destination
+            && destination.header_version() != version
+        {
+            return Err(BuildError::DestinationMismatch);
+        }
+
+        if self
+            .hardware_versions
+            .as_ref()
+            .is_some_and(RangeInclusive::is_empty)
+        {
+            return Err(BuildError::EmptyHardwareVersions);
+        }
+
+        let name_length = self.name.len();
+        let mut name = [0; HEADER_STRING_LENGTH];
+        name.get_mut(..name_length)
+            .ok_or(BuildError::NameTooLong(name_length))?
+            .copy_from_slice(&self.name);
+
+        let mut field_control = FieldControl::empty();
+        let mut length = OtaFile::MIN_HEADER_LENGTH;
+
+        if self.security_credentials.is_some() {
+            field_control |= FieldControl::SECURITY_CREDENTIAL_VERSION_FIELD_PRESENT_MASK;
+            length = length.saturating_add(1);
+        }
+
+        if let Some(upgrade_file_destination) = &self.upgrade_file_destination {
+            field_control |= FieldControl::DEVICE_SPECIFIC_FILE_PRESENT_MASK;
+            length = length.saturating_add(upgrade_file_destination.size());
+        }
+
+        if self.hardware_versions.is_some() {
+            field_control |= FieldControl::HARDWARE_VERSIONS_PRESENT_MASK;
+            length = length.saturating_add(4);
+        }
+
+        let image_size = self
+            .tags
+            .iter()
+            .try_fold(u32::from(length), |size, tag| {
+                size.checked_add(Tag::HEADER_SIZE)?
+                    .checked_add(tag.length())
+            })
+            .ok_or(BuildError::ImageTooLarge)?;
+
+        Ok(OtaFile {
+            magic: MAGIC,
+            header: Header::new(
+                version.into(),
+                length,
+                field_control,
+                self.manufacturer_id,
+                self.image_type,
+                self.firmware_version,
+                self.zigbee_stack_version,
+                name,
+                image_size,
+            ),
+            security_credentials: self.security_credentials,
+            upgrade_file_destination: self.upgrade_file_destination,
+            hardware_versions: self.hardware_versions,
+            header_extension: Vec::new(),
+            tags: self.tags,
+        })
+    }
+}
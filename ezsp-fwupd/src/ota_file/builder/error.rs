@@ -0,0 +1,36 @@
+use std::fmt::Display;
+
+/// Errors that can occur when building an [`OtaFile`](crate::OtaFile).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum BuildError {
+    /// The name exceeds the maximum header string length of 32 bytes.
+    NameTooLong(usize),
+    /// The upgrade file destination does not match the header version.
+    DestinationMismatch,
+    /// The total image size exceeds the maximum of `u32::MAX` bytes.
+    ImageTooLarge,
+    /// The minimum hardware version exceeds the maximum hardware version.
+    EmptyHardwareVersions,
+}
+
+impl Display for BuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NameTooLong(length) => {
+                write!(f, "Name too long: {length} bytes (max. 32 bytes)")
+            }
+            Self::DestinationMismatch => {
+                write!(f, "Upgrade file destination does not match header version")
+            }
+            Self::ImageTooLarge => write!(f, "Image size exceeds {} bytes", u32::MAX),
+            Self::EmptyHardwareVersions => {
+                write!(
+                    f,
+                    "Minimum hardware version exceeds maximum hardware version"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
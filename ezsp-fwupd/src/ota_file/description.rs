@@ -0,0 +1,52 @@
+use std::fmt::Display;
+
+use super::{OtaFile, Registry};
+
+/// Displays an [`OtaFile`], resolving manufacturer and image type names through a [`Registry`].
+pub struct Description<'ota> {
+    ota_file: &'ota OtaFile,
+    registry: &'ota Registry,
+}
+
+impl<'ota> Description<'ota> {
+    /// Create a new description.
+    pub(super) const fn new(ota_file: &'ota OtaFile, registry: &'ota Registry) -> Self {
+        Self { ota_file, registry }
+    }
+}
+
+impl Display for Description<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ota_file = self.ota_file;
+        ota_file.header.describe(self.registry).fmt(f)?;
+
+        if let Some(security_credentials) = ota_file.security_credentials {
+            write!(f, "\nOTA credentials:   {security_credentials:#04X}")?;
+        }
+
+        if let Some(upgrade_file_destination) = &ota_file.upgrade_file_destination {
+            write!(f, "\nOTA destination:   {upgrade_file_destination}")?;
+        }
+
+        if let Some(hardware_versions) = &ota_file.hardware_versions {
+            write!(
+                f,
+                "\nOTA hardware:      {}..={}",
+                hardware_versions.start(),
+                hardware_versions.end()
+            )?;
+        }
+
+        for tag in &ota_file.tags {
+            write!(f, "\nOTA tag {:#06X}:    ", tag.id())?;
+
+            if let Some(kind) = tag.kind() {
+                kind.fmt(f)?;
+            } else {
+                write!(f, "Malformed ({} bytes)", tag.length())?;
+            }
+        }
+
+        Ok(())
+    }
+}
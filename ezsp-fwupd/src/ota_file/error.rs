@@ -0,0 +1,170 @@
+use std::fmt::Display;
+
+pub use self::field::Field;
+use super::Magic;
+
+mod field;
+
+/// Byte offset of the header version field.
+const HEADER_VERSION_OFFSET: usize = 4;
+
+/// Byte offset of the header length field.
+const HEADER_LENGTH_OFFSET: usize = 6;
+
+/// Byte offset of the total image size field.
+const IMAGE_SIZE_OFFSET: usize = 52;
+
+/// Errors that can occur when parsing an [`OtaFile`](super::OtaFile).
+#[derive(Debug)]
+pub enum OtaError {
+    /// Reading the OTA file failed.
+    Io(std::io::Error),
+    /// The data ended before the given field could be read.
+    Truncated {
+        /// The byte offset of the field.
+        offset: usize,
+        /// The field that could not be read.
+        field: Field,
+    },
+    /// The OTA file's magic number does not match.
+    InvalidMagic(Magic),
+    /// The header version is neither Zigbee nor Thread.
+    UnknownHeaderVersion(u16),
+    /// The header length is smaller than the header's fixed fields.
+    HeaderTooShort(u16),
+    /// The header length is smaller than the fields announced by the field control.
+    HeaderLengthMismatch {
+        /// The header length stored in the header.
+        length: u16,
+        /// The length of the fields announced by the field control.
+        expected: usize,
+    },
+    /// The total image size is smaller than the header length.
+    ImageSizeTooSmall {
+        /// The total image size stored in the header.
+        image_size: u32,
+        /// The header length stored in the header.
+        header_length: u16,
+    },
+    /// The total image size does not match the size of the file.
+    ImageSizeMismatch {
+        /// The total image size stored in the header.
+        image_size: u32,
+        /// The actual size of the file.
+        file_size: usize,
+    },
+    /// A tag exceeds the total image size.
+    TagOverrun {
+        /// The byte offset of the tag.
+        offset: usize,
+        /// The tag ID.
+        id: u16,
+        /// The length of the tag's data.
+        length: u32,
+        /// The amount of bytes remaining in the image, including the tag's header.
+        remaining: u32,
+    },
+}
+
+impl OtaError {
+    /// Return the byte offset of the field that failed to parse, if applicable.
+    #[must_use]
+    pub const fn offset(&self) -> Option<usize> {
+        match self {
+            Self::Io(_) => None,
+            Self::InvalidMagic(_) => Some(0),
+            Self::UnknownHeaderVersion(_) => Some(HEADER_VERSION_OFFSET),
+            Self::HeaderTooShort(_) | Self::HeaderLengthMismatch { .. } => {
+                Some(HEADER_LENGTH_OFFSET)
+            }
+            Self::ImageSizeTooSmall { .. } | Self::ImageSizeMismatch { .. } => {
+                Some(IMAGE_SIZE_OFFSET)
+            }
+            Self::Truncated { offset, .. } | Self::TagOverrun { offset, .. } => Some(*offset),
+        }
+    }
+
+    /// Return the field that failed to parse, if applicable.
+    #[must_use]
+    pub const fn field(&self) -> Option<Field> {
+        match self {
+            Self::Io(_) => None,
+            Self::Truncated { field, .. } => Some(*field),
+            Self::InvalidMagic(_) => Some(Field::Magic),
+            Self::UnknownHeaderVersion(_) => Some(Field::HeaderVersion),
+            Self::HeaderTooShort(_) | Self::HeaderLengthMismatch { .. } => {
+                Some(Field::HeaderLength)
+            }
+            Self::ImageSizeTooSmall { .. } | Self::ImageSizeMismatch { .. } => {
+                Some(Field::ImageSize)
+            }
+            Self::TagOverrun { .. } => Some(Field::TagLength),
+        }
+    }
+}
+
+impl Display for OtaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "Failed to read OTA file: {error}"),
+            Self::Truncated { offset, field } => {
+                write!(
+                    f,
+                    "OTA file truncated at offset {offset} while reading {field}"
+                )
+            }
+            Self::InvalidMagic(magic) => write!(f, "Invalid OTA file magic: {magic:#04X?}"),
+            Self::UnknownHeaderVersion(version) => {
+                write!(f, "Unknown OTA header version: {version:#06X}")
+            }
+            Self::HeaderTooShort(length) => {
+                write!(
+                    f,
+                    "OTA header length {length} is smaller than the fixed header"
+                )
+            }
+            Self::HeaderLengthMismatch { length, expected } => write!(
+                f,
+                "OTA header length {length} is smaller than the header fields' length {expected}"
+            ),
+            Self::ImageSizeTooSmall {
+                image_size,
+                header_length,
+            } => write!(
+                f,
+                "OTA image size {image_size} is smaller than the header length {header_length}"
+            ),
+            Self::ImageSizeMismatch {
+                image_size,
+                file_size,
+            } => write!(
+                f,
+                "OTA image size {image_size} does not match the file size {file_size}"
+            ),
+            Self::TagOverrun {
+                offset,
+                id,
+                length,
+                remaining,
+            } => write!(
+                f,
+                "OTA tag {id:#06X} at offset {offset} with length {length} exceeds the remaining {remaining} bytes of the image"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for OtaError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for OtaError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
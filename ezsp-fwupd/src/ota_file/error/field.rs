@@ -0,0 +1,67 @@
+use std::fmt::Display;
+
+/// The fields of an OTA file.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Field {
+    /// The OTA upgrade file identifier.
+    Magic,
+    /// The OTA header version.
+    HeaderVersion,
+    /// The OTA header length.
+    HeaderLength,
+    /// The OTA header field control.
+    FieldControl,
+    /// The manufacturer code.
+    ManufacturerId,
+    /// The image type.
+    ImageType,
+    /// The file version.
+    FileVersion,
+    /// The Zigbee stack version.
+    ZigbeeStackVersion,
+    /// The OTA header string.
+    HeaderString,
+    /// The total image size including the header.
+    ImageSize,
+    /// The security credential version.
+    SecurityCredentialVersion,
+    /// The upgrade file destination.
+    UpgradeFileDestination,
+    /// The minimum hardware version.
+    MinimumHardwareVersion,
+    /// The maximum hardware version.
+    MaximumHardwareVersion,
+    /// Header fields following the known fields.
+    HeaderExtension,
+    /// The tag ID of a sub-element.
+    TagId,
+    /// The length of a sub-element.
+    TagLength,
+    /// The data of a sub-element.
+    TagData,
+}
+
+impl Display for Field {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Magic => write!(f, "magic"),
+            Self::HeaderVersion => write!(f, "header version"),
+            Self::HeaderLength => write!(f, "header length"),
+            Self::FieldControl => write!(f, "field control"),
+            Self::ManufacturerId => write!(f, "manufacturer ID"),
+            Self::ImageType => write!(f, "image type"),
+            Self::FileVersion => write!(f, "file version"),
+            Self::ZigbeeStackVersion => write!(f, "Zigbee stack version"),
+            Self::HeaderString => write!(f, "header string"),
+            Self::ImageSize => write!(f, "total image size"),
+            Self::SecurityCredentialVersion => write!(f, "security credential version"),
+            Self::UpgradeFileDestination => write!(f, "upgrade file destination"),
+            Self::MinimumHardwareVersion => write!(f, "minimum hardware version"),
+            Self::MaximumHardwareVersion => write!(f, "maximum hardware version"),
+            Self::HeaderExtension => write!(f, "header extension"),
+            Self::TagId => write!(f, "tag ID"),
+            Self::TagLength => write!(f, "tag length"),
+            Self::TagData => write!(f, "tag data"),
+        }
+    }
+}
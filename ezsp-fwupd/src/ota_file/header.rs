@@ -1,18 +1,26 @@
 use std::borrow::Cow;
 use std::fmt::Display;
 
-use le_stream::FromLeStream;
+use le_stream::{FromLeStream, ToLeStream};
 use log::info;
 
-use self::field_control::FieldControl;
-use super::tag::Tag;
+pub use self::field_control::FieldControl;
+pub use self::firmware_version::{FirmwareVersion, ReleaseType};
+pub use self::header_version::HeaderVersion;
+pub use self::stack_version::StackVersion;
+use super::Registry;
 
-const HEADER_STRING_LENGTH: usize = 32;
+/// Length of the header string, i.e. the OTA file's name.
+pub const HEADER_STRING_LENGTH: usize = 32;
 
 mod field_control;
+mod firmware_version;
+mod header_version;
+mod stack_version;
 
 /// Represents the header of an OTA (Over-The-Air) file used for firmware updates.
-#[derive(Clone, Debug, Eq, Hash, PartialEq, FromLeStream)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq, FromLeStream, ToLeStream)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Header {
     version: u16,
     length: u16,
@@ -21,17 +29,54 @@ pub struct Header {
     image_type: u16,
     firmware_version: u32,
     zigbee_stack_version: u16,
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_name"))]
     name: [u8; HEADER_STRING_LENGTH],
     image_size: u32,
 }
 
 impl Header {
+    /// Size of the header's fixed fields in bytes.
+    pub const SIZE: u16 = 52;
+
+    /// Create a new header.
+    #[expect(clippy::too_many_arguments)]
+    #[must_use]
+    pub(crate) const fn new(
+        version: u16,
+        length: u16,
+        field_control: FieldControl,
+        manufacturer_id: u16,
+        image_type: u16,
+        firmware_version: u32,
+        zigbee_stack_version: u16,
+        name: [u8; HEADER_STRING_LENGTH],
+        image_size: u32,
+    ) -> Self {
+        Self {
+            version,
+            length,
+            field_control,
+            manufacturer_id,
+            image_type,
+            firmware_version,
+            zigbee_stack_version,
+            name,
+            image_size,
+        }
+    }
+
     /// Return the version.
     #[must_use]
     pub const fn version(&self) -> u16 {
         self.version
     }
 
+    /// Return the header version, if it is known.
+    #[must_use]
+    pub fn header_version(&self) -> Option<HeaderVersion> {
+        HeaderVersion::try_from(self.version).ok()
+    }
+
     /// Return the header's length.
     #[must_use]
     pub const fn length(&self) -> u16 {
@@ -62,12 +107,24 @@ impl Header {
         self.firmware_version
     }
 
+    /// Return the firmware version decoded according to the `EmberZNet` file version layout.
+    #[must_use]
+    pub const fn decoded_firmware_version(&self) -> FirmwareVersion {
+        FirmwareVersion::from_u32(self.firmware_version)
+    }
+
     /// Return the Zigbee stack version.
     #[must_use]
     pub const fn zigbee_stack_version(&self) -> u16 {
         self.zigbee_stack_version
     }
 
+    /// Return the Zigbee stack version, if it is known.
+    #[must_use]
+    pub fn stack_version(&self) -> Option<StackVersion> {
+        StackVersion::try_from(self.zigbee_stack_version).ok()
+    }
+
     /// Return the name of the OTA file.
     #[must_use]
     pub fn name(&self) -> Cow<'_, str> {
@@ -80,47 +137,103 @@ impl Header {
         self.image_size
     }
 
-    /// Parse the tags from the OTA file.
+    /// Return a description of the header that resolves names through the given registry.
     #[must_use]
-    pub(crate) fn parse_tags<T>(&self, mut bytes: T) -> Vec<Tag>
-    where
-        T: Iterator<Item = u8>,
-    {
-        let mut tags = Vec::new();
-        let mut limit = self.image_size() - u32::from(self.length());
-
-        while limit > 0 {
-            if let Some(tag) = Tag::from_le_stream(&mut bytes) {
-                limit = limit.saturating_sub(tag.length()).saturating_sub(Tag::SIZE);
-                tags.push(tag);
-            } else {
-                return tags;
-            }
+    pub const fn describe<'header>(
+        &'header self,
+        registry: &'header Registry,
+    ) -> HeaderDescription<'header> {
+        HeaderDescription {
+            header: self,
+            registry,
         }
-
-        tags
     }
 
-    /// Log the header information.
+    /// Log the header information, resolving names through the built-in registry.
     pub fn log(&self) {
-        info!("OTA image name:    {}", self.name());
-        info!("OTA image type:    {}", self.image_type());
-        info!("OTA image version: {}", self.version());
-        info!("OTA file version:  {}", self.firmware_version());
-        info!("OTA Zigbee stack:  {}", self.zigbee_stack_version());
-        info!("OTA manufacturer:  {}", self.manufacturer_id());
-        info!("OTA image size:    {}", self.image_size());
+        self.log_with(Registry::builtin());
+    }
+
+    /// Log the header information, resolving names through the given registry.
+    pub fn log_with(&self, registry: &Registry) {
+        for line in self.describe(registry).to_string().lines() {
+            info!("{line}");
+        }
     }
 }
 
 impl Display for Header {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "OTA image name:    {}", self.name())?;
-        writeln!(f, "OTA image type:    {}", self.image_type())?;
-        writeln!(f, "OTA image version: {}", self.version())?;
-        writeln!(f, "OTA file version:  {}", self.firmware_version())?;
-        writeln!(f, "OTA Zigbee stack:  {}", self.zigbee_stack_version())?;
-        writeln!(f, "OTA manufacturer:  {}", self.manufacturer_id())?;
-        write!(f, "OTA image size:    {}", self.image_size())
+        self.describe(Registry::builtin()).fmt(f)
+    }
+}
+
+/// Displays a [`Header`], resolving manufacturer and image type names through a [`Registry`].
+pub struct HeaderDescription<'header> {
+    header: &'header Header,
+    registry: &'header Registry,
+}
+
+impl Display for HeaderDescription<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let header = self.header;
+        writeln!(f, "OTA image name:    {}", header.name())?;
+        writeln!(
+            f,
+            "OTA image type:    {}",
+            self.registry
+                .display_image_type(header.manufacturer_id, header.image_type)
+        )?;
+        writeln!(f, "OTA header:        {}", DisplayVersion(header.version))?;
+        writeln!(
+            f,
+            "OTA file version:  {} ({:#010X})",
+            header.decoded_firmware_version(),
+            header.firmware_version
+        )?;
+        writeln!(
+            f,
+            "OTA Zigbee stack:  {}",
+            DisplayStack(header.zigbee_stack_version)
+        )?;
+        writeln!(
+            f,
+            "OTA manufacturer:  {}",
+            self.registry.display_manufacturer(header.manufacturer_id)
+        )?;
+        write!(f, "OTA image size:    {}", header.image_size)
+    }
+}
+
+/// Serialize the header string as a string without trailing NUL bytes.
+#[cfg(feature = "serde")]
+fn serialize_name<S>(name: &[u8; HEADER_STRING_LENGTH], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_str(String::from_utf8_lossy(name).trim_end_matches('\0'))
+}
+
+/// Displays a header version by name if it is known and as hex otherwise.
+struct DisplayVersion(u16);
+
+impl Display for DisplayVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match HeaderVersion::try_from(self.0) {
+            Ok(version) => version.fmt(f),
+            Err(version) => write!(f, "Unknown ({version:#06X})"),
+        }
+    }
+}
+
+/// Displays a Zigbee stack version by name if it is known and as hex otherwise.
+struct DisplayStack(u16);
+
+impl Display for DisplayStack {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match StackVersion::try_from(self.0) {
+            Ok(version) => write!(f, "{version} ({:#06X})", self.0),
+            Err(version) => write!(f, "Unknown ({version:#06X})"),
+        }
     }
 }
@@ -1,14 +1,18 @@
 use bitflags::bitflags;
-use le_stream::FromLeStream;
+use le_stream::{FromLeStream, ToLeStream};
 
 /// Represents the field control flags in an OTA (Over-The-Air) file header.
-#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, FromLeStream)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, FromLeStream, ToLeStream)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct FieldControl(u16);
 
 bitflags! {
     impl FieldControl: u16 {
+        /// The security credential version field is present.
         const SECURITY_CREDENTIAL_VERSION_FIELD_PRESENT_MASK = 0b0000_0000_0000_0001;
+        /// The file is device-specific, i.e. the upgrade file destination is present.
         const DEVICE_SPECIFIC_FILE_PRESENT_MASK = 0b0000_0000_0000_0010;
+        /// The minimum and maximum hardware versions are present.
         const HARDWARE_VERSIONS_PRESENT_MASK = 0b0000_0000_0000_0100;
     }
 }
@@ -0,0 +1,163 @@
+use std::fmt::Display;
+
+use semver::{BuildMetadata, Prerelease, Version};
+
+pub use self::release_type::ReleaseType;
+
+mod release_type;
+
+/// An OTA file version decoded according to the `EmberZNet` file version layout.
+///
+/// | Bits      | Content                        |
+/// |-----------|--------------------------------|
+/// | `31..=28` | Major version                  |
+/// | `27..=24` | Minor version                  |
+/// | `23..=20` | Patch version                  |
+/// | `19..=16` | Special version                |
+/// | `15..=8`  | Release type                   |
+/// | `7..=0`   | Build number                   |
+///
+/// The upper 16 bits correspond to `EMBER_FULL_VERSION` and the release type to `EMBER_VERSION_TYPE`.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct FirmwareVersion(u32);
+
+impl FirmwareVersion {
+    /// Create a firmware version from its raw representation.
+    #[must_use]
+    pub const fn from_u32(version: u32) -> Self {
+        Self(version)
+    }
+
+    /// Return the major version.
+    #[must_use]
+    pub const fn major(self) -> u8 {
+        self.nibble(28)
+    }
+
+    /// Return the minor version.
+    #[must_use]
+    pub const fn minor(self) -> u8 {
+        self.nibble(24)
+    }
+
+    /// Return the patch version.
+    #[must_use]
+    pub const fn patch(self) -> u8 {
+        self.nibble(20)
+    }
+
+    /// Return the special version, i.e. the fourth component of an `EmberZNet` version.
+    #[must_use]
+    pub const fn special(self) -> u8 {
+        self.nibble(16)
+    }
+
+    /// Return the release type, if it is known.
+    #[must_use]
+    pub const fn release_type(self) -> Option<ReleaseType> {
+        ReleaseType::from_u8(self.0.to_be_bytes()[2])
+    }
+
+    /// Return the build number.
+    #[must_use]
+    pub const fn build(self) -> u8 {
+        self.0.to_be_bytes()[3]
+    }
+
+    /// Return the semantic version.
+    ///
+    /// Alpha, beta and pre-releases are represented as pre-release identifiers.
+    /// The special version and the build number are stored as build metadata.
+    #[must_use]
+    pub fn semver(self) -> Version {
+        Version {
+            major: self.major().into(),
+            minor: self.minor().into(),
+            patch: self.patch().into(),
+            pre: self
+                .release_type()
+                .and_then(ReleaseType::pre_release)
+                .and_then(|pre| Prerelease::new(&pre).ok())
+                .unwrap_or_default(),
+            build: BuildMetadata::new(&format!("{}.{}", self.special(), self.build()))
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Return the four bits starting at the given bit offset.
+    const fn nibble(self, offset: u32) -> u8 {
+        (self.0.wrapping_shr(offset) & 0x0F) as u8
+    }
+}
+
+impl Display for FirmwareVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}.{}.{}.{}",
+            self.major(),
+            self.minor(),
+            self.patch(),
+            self.special()
+        )?;
+
+        if let Some(release_type) = self.release_type() {
+            write!(f, " {release_type}")?;
+        }
+
+        write!(f, " build {}", self.build())
+    }
+}
+
+impl From<u32> for FirmwareVersion {
+    fn from(version: u32) -> Self {
+        Self(version)
+    }
+}
+
+impl From<FirmwareVersion> for u32 {
+    fn from(version: FirmwareVersion) -> Self {
+        version.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use semver::Version;
+
+    use super::{FirmwareVersion, ReleaseType};
+
+    #[test]
+    fn test_general_availability() {
+        let version = FirmwareVersion::from(0x7430_AA7B);
+        assert_eq!(version.major(), 7);
+        assert_eq!(version.minor(), 4);
+        assert_eq!(version.patch(), 3);
+        assert_eq!(version.special(), 0);
+        assert_eq!(
+            version.release_type(),
+            Some(ReleaseType::GeneralAvailability)
+        );
+        assert_eq!(version.build(), 123);
+        assert_eq!(version.semver(), Version::parse("7.4.3+0.123").unwrap());
+        assert_eq!(version.to_string(), "7.4.3.0 GA build 123");
+    }
+
+    #[test]
+    fn test_beta() {
+        let version = FirmwareVersion::from(0x8010_2205);
+        assert_eq!(version.release_type(), Some(ReleaseType::Beta2));
+        assert_eq!(
+            version.semver(),
+            Version::parse("8.0.1-beta.2+0.5").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_unknown_release_type() {
+        let version = FirmwareVersion::from(0x7431_0101);
+        assert_eq!(version.release_type(), None);
+        assert_eq!(version.semver(), Version::parse("7.4.3+1.1").unwrap());
+        assert_eq!(version.to_string(), "7.4.3.1 build 1");
+    }
+}
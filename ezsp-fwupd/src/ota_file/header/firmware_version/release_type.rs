@@ -0,0 +1,77 @@
+use std::fmt::Display;
+
+/// `EmberZNet` release types as stored in `EMBER_VERSION_TYPE`.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[repr(u8)]
+pub enum ReleaseType {
+    /// Pre-release.
+    PreRelease = 0x00,
+    /// First alpha release.
+    Alpha1 = 0x11,
+    /// Second alpha release.
+    Alpha2 = 0x12,
+    /// Third alpha release.
+    Alpha3 = 0x13,
+    /// First beta release.
+    Beta1 = 0x21,
+    /// Second beta release.
+    Beta2 = 0x22,
+    /// Third beta release.
+    Beta3 = 0x23,
+    /// General availability release.
+    GeneralAvailability = 0xAA,
+}
+
+impl ReleaseType {
+    /// Return the release type for the given `EMBER_VERSION_TYPE` value, if it is known.
+    #[must_use]
+    pub const fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0x00 => Some(Self::PreRelease),
+            0x11 => Some(Self::Alpha1),
+            0x12 => Some(Self::Alpha2),
+            0x13 => Some(Self::Alpha3),
+            0x21 => Some(Self::Beta1),
+            0x22 => Some(Self::Beta2),
+            0x23 => Some(Self::Beta3),
+            0xAA => Some(Self::GeneralAvailability),
+            _ => None,
+        }
+    }
+
+    /// Return the semantic version pre-release identifier, if this is not a general availability release.
+    #[must_use]
+    pub fn pre_release(self) -> Option<String> {
+        match self {
+            Self::PreRelease => Some("pre".to_string()),
+            Self::Alpha1 | Self::Alpha2 | Self::Alpha3 => {
+                Some(format!("alpha.{}", u8::from(self) & 0x0F))
+            }
+            Self::Beta1 | Self::Beta2 | Self::Beta3 => {
+                Some(format!("beta.{}", u8::from(self) & 0x0F))
+            }
+            Self::GeneralAvailability => None,
+        }
+    }
+}
+
+impl Display for ReleaseType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PreRelease => write!(f, "pre-release"),
+            Self::Alpha1 | Self::Alpha2 | Self::Alpha3 => {
+                write!(f, "alpha {}", u8::from(*self) & 0x0F)
+            }
+            Self::Beta1 | Self::Beta2 | Self::Beta3 => {
+                write!(f, "beta {}", u8::from(*self) & 0x0F)
+            }
+            Self::GeneralAvailability => write!(f, "GA"),
+        }
+    }
+}
+
+impl From<ReleaseType> for u8 {
+    fn from(release_type: ReleaseType) -> Self {
+        release_type as Self
+    }
+}
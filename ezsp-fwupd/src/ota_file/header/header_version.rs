@@ -0,0 +1,49 @@
+use std::fmt::Display;
+
+/// Known versions of the OTA file header.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[repr(u16)]
+pub enum HeaderVersion {
+    /// Zigbee OTA header, whose upgrade file destination is an EUI-64.
+    Zigbee = 0x0100,
+    /// Thread OTA header, whose upgrade file destination is a 32-byte Thread ID.
+    Thread = 0x0200,
+}
+
+impl HeaderVersion {
+    /// Return the size of the upgrade file destination in bytes.
+    #[must_use]
+    pub const fn destination_size(self) -> u16 {
+        match self {
+            Self::Zigbee => 8,
+            Self::Thread => 32,
+        }
+    }
+}
+
+impl Display for HeaderVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Zigbee => write!(f, "Zigbee ({:#06X})", u16::from(*self)),
+            Self::Thread => write!(f, "Thread ({:#06X})", u16::from(*self)),
+        }
+    }
+}
+
+impl From<HeaderVersion> for u16 {
+    fn from(version: HeaderVersion) -> Self {
+        version as Self
+    }
+}
+
+impl TryFrom<u16> for HeaderVersion {
+    type Error = u16;
+
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        match value {
+            0x0100 => Ok(Self::Zigbee),
+            0x0200 => Ok(Self::Thread),
+            other => Err(other),
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use std::fmt::Display;
+
+/// Zigbee stack versions as stored in the OTA file header.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[repr(u16)]
+pub enum StackVersion {
+    /// Zigbee 2006.
+    Zigbee2006 = 0x0000,
+    /// Zigbee 2007.
+    Zigbee2007 = 0x0001,
+    /// Zigbee PRO.
+    ZigbeePro = 0x0002,
+    /// Zigbee IP.
+    ZigbeeIp = 0x0003,
+}
+
+impl Display for StackVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Zigbee2006 => write!(f, "Zigbee 2006"),
+            Self::Zigbee2007 => write!(f, "Zigbee 2007"),
+            Self::ZigbeePro => write!(f, "Zigbee PRO"),
+            Self::ZigbeeIp => write!(f, "Zigbee IP"),
+        }
+    }
+}
+
+impl From<StackVersion> for u16 {
+    fn from(version: StackVersion) -> Self {
+        version as Self
+    }
+}
+
+impl TryFrom<u16> for StackVersion {
+    type Error = u16;
+
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        match value {
+            0x0000 => Ok(Self::Zigbee2006),
+            0x0001 => Ok(Self::Zigbee2007),
+            0x0002 => Ok(Self::ZigbeePro),
+            0x0003 => Ok(Self::ZigbeeIp),
+            other => Err(other),
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use std::io::Write;
+
+use sha2::{Digest, Sha256};
+
+use super::aes_mmo::{self, AesMmo};
+use super::{Tag, TagKind, ValidationError};
+
+/// Incremental calculation of an image integrity code.
+///
+/// The image integrity code is an AES-MMO hash if it is 16 bytes long and a SHA-256 hash if it is 32 bytes long.
+#[derive(Clone, Debug)]
+pub enum IntegrityCode {
+    AesMmo(AesMmo),
+    Sha256(Sha256),
+}
+
+impl IntegrityCode {
+    /// Create a hasher for the given image integrity code tag.
+    ///
+    /// Returns the hasher along with the expected hash stored in the tag.
+    pub fn for_tag(tag: &Tag) -> Result<(Self, &[u8]), ValidationError> {
+        let Some(TagKind::ImageIntegrityCode(expected)) = tag.kind() else {
+            return Err(ValidationError::InvalidIntegrityCode(tag.data().len()));
+        };
+
+        let hasher = if expected.len() == aes_mmo::SIZE {
+            Self::AesMmo(AesMmo::default())
+        } else {
+            Self::Sha256(Sha256::new())
+        };
+
+        Ok((hasher, expected))
+    }
+
+    /// Feed the given data into the hash.
+    pub fn update(&mut self, data: &[u8]) {
+        match self {
+            Self::AesMmo(hasher) => hasher.update(data),
+            Self::Sha256(hasher) => hasher.update(data),
+        }
+    }
+
+    /// Compare the calculated hash against the expected image integrity code.
+    pub fn verify(self, expected: &[u8]) -> Result<(), ValidationError> {
+        let calculated = match self {
+            Self::AesMmo(hasher) => hasher.finalize().to_vec(),
+            Self::Sha256(hasher) => hasher.finalize().to_vec(),
+        };
+
+        if calculated == expected {
+            Ok(())
+        } else {
+            Err(ValidationError::IntegrityCodeMismatch {
+                expected: expected.to_vec(),
+                calculated,
+            })
+        }
+    }
+}
+
+impl Write for IntegrityCode {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
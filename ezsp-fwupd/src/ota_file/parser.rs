@@ -0,0 +1,209 @@
+use std::ops::RangeInclusive;
+
+use ezsp::ember::Eui64;
+use le_stream::FromLeStream;
+
+pub use self::tags::Tags;
+use super::{
+    Field, FieldControl, Header, HeaderVersion, MAGIC, Magic, OtaError, OtaFile, Tag, ThreadId,
+    UpgradeFileDestination,
+};
+
+mod tags;
+
+/// Parses an OTA file while keeping track of the current byte offset.
+pub struct Parser<T> {
+    bytes: T,
+    offset: usize,
+    file_size: Option<usize>,
+}
+
+impl<T> Parser<T>
+where
+    T: Iterator<Item = u8>,
+{
+    /// Create a new parser.
+    ///
+    /// If the file size is known, it is checked against the total image size stored in the header.
+    pub const fn new(bytes: T, file_size: Option<usize>) -> Self {
+        Self {
+            bytes,
+            offset: 0,
+            file_size,
+        }
+    }
+
+    /// Create a parser for bytes starting at the given offset within the file.
+    pub const fn at(bytes: T, offset: usize) -> Self {
+        Self {
+            bytes,
+            offset,
+            file_size: None,
+        }
+    }
+
+    /// Parse the OTA file.
+    pub fn parse(mut self) -> Result<OtaFile, OtaError> {
+        let (mut ota_file, remaining) = self.parse_header()?;
+        ota_file.tags = Tags::from_parser(self, remaining).collect::<Result<_, _>>()?;
+        Ok(ota_file)
+    }
+
+    /// Parse the OTA file's header, including its optional fields.
+    ///
+    /// Returns the OTA file without any tags and the total size of its tags in bytes.
+    pub fn parse_header(&mut self) -> Result<(OtaFile, u32), OtaError> {
+        let magic: Magic = self.read(Field::Magic)?;
+
+        if magic != MAGIC {
+            return Err(OtaError::InvalidMagic(magic));
+        }
+
+        let version = HeaderVersion::try_from(self.read::<u16>(Field::HeaderVersion)?)
+            .map_err(OtaError::UnknownHeaderVersion)?;
+
+        let length: u16 = self.read(Field::HeaderLength)?;
+
+        if length < OtaFile::MIN_HEADER_LENGTH {
+            return Err(OtaError::HeaderTooShort(length));
+        }
+
+        let field_control: FieldControl = self.read(Field::FieldControl)?;
+        let header = Header::new(
+            version.into(),
+            length,
+            field_control,
+            self.read(Field::ManufacturerId)?,
+            self.read(Field::ImageType)?,
+            self.read(Field::FileVersion)?,
+            self.read(Field::ZigbeeStackVersion)?,
+            self.read(Field::HeaderString)?,
+            self.read(Field::ImageSize)?,
+        );
+        let image_size = header.image_size();
+
+        if let Some(file_size) = self.file_size
+            && usize::try_from(image_size).ok() != Some(file_size)
+        {
+            return Err(OtaError::ImageSizeMismatch {
+                image_size,
+                file_size,
+            });
+        }
+
+        let security_credentials = if field_control.has_security_credentials() {
+            Some(self.read(Field::SecurityCredentialVersion)?)
+        } else {
+            None
+        };
+
+        let upgrade_file_destination = if field_control.has_upgrade_file_destination() {
+            Some(match version {
+                HeaderVersion::Zigbee => UpgradeFileDestination::Zigbee(
+                    self.read::<Eui64>(Field::UpgradeFileDestination)?,
+                ),
+                HeaderVersion::Thread => UpgradeFileDestination::Thread(Box::new(
+                    self.read::<ThreadId>(Field::UpgradeFileDestination)?,
+                )),
+            })
+        } else {
+            None
+        };
+
+        let hardware_versions = if field_control.has_hardware_version() {
+            Some(RangeInclusive::new(
+                self.read(Field::MinimumHardwareVersion)?,
+                self.read(Field::MaximumHardwareVersion)?,
+            ))
+        } else {
+            None
+        };
+
+        // Newer header versions may append fields, which the header length allows to skip.
+        let header_extension = usize::from(length)
+            .checked_sub(self.offset)
+            .ok_or(OtaError::HeaderLengthMismatch {
+                length,
+                expected: self.offset,
+            })
+            .and_then(|extension| {
+                self.read_bytes(
+                    u32::try_from(extension).unwrap_or(u32::MAX),
+                    Field::HeaderExtension,
+                )
+            })?;
+
+        let remaining =
+            image_size
+                .checked_sub(u32::from(length))
+                .ok_or(OtaError::ImageSizeTooSmall {
+                    image_size,
+                    header_length: length,
+                })?;
+
+        Ok((
+            OtaFile {
+                magic,
+                header,
+                security_credentials,
+                upgrade_file_destination,
+                hardware_versions,
+                header_extension,
+                tags: Vec::new(),
+            },
+            remaining,
+        ))
+    }
+
+    /// Read a tag's ID and length, checking that the tag does not exceed the remaining bytes of the image.
+    ///
+    /// Returns the tag's offset, ID and length and deducts the tag's size from the remaining bytes.
+    pub fn read_tag_header(&mut self, remaining: &mut u32) -> Result<(usize, u16, u32), OtaError> {
+        let offset = self.offset;
+        let id: u16 = self.read(Field::TagId)?;
+        let length: u32 = self.read(Field::TagLength)?;
+        *remaining = remaining
+            .checked_sub(Tag::HEADER_SIZE)
+            .and_then(|remaining| remaining.checked_sub(length))
+            .ok_or(OtaError::TagOverrun {
+                offset,
+                id,
+                length,
+                remaining: *remaining,
+            })?;
+        Ok((offset, id, length))
+    }
+
+    /// Read a value, advancing the offset by the amount of bytes consumed.
+    fn read<V>(&mut self, field: Field) -> Result<V, OtaError>
+    where
+        V: FromLeStream,
+    {
+        let offset = self.offset;
+        let mut consumed: usize = 0;
+        let value = V::from_le_stream(
+            self.bytes
+                .by_ref()
+                .inspect(|_| consumed = consumed.saturating_add(1)),
+        );
+        self.offset = self.offset.saturating_add(consumed);
+        value.ok_or(OtaError::Truncated { offset, field })
+    }
+
+    /// Read the given amount of raw bytes, advancing the offset accordingly.
+    fn read_bytes(&mut self, length: u32, field: Field) -> Result<Vec<u8>, OtaError> {
+        let offset = self.offset;
+        let data: Vec<u8> = self
+            .bytes
+            .by_ref()
+            .take(usize::try_from(length).unwrap_or(usize::MAX))
+            .collect();
+        self.offset = self.offset.saturating_add(data.len());
+
+        if u32::try_from(data.len()).ok() == Some(length) {
+            Ok(data)
+        } else {
+            Err(OtaError::Truncated { offset, field })
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use std::iter::FusedIterator;
+
+use super::Parser;
+use crate::ota_file::{Field, OtaError, Tag};
+
+/// Iterator over the tags (sub-elements) of an OTA file.
+///
+/// The iterator stops once the given amount of bytes has been parsed or after the first error.
+pub struct Tags<T> {
+    parser: Parser<T>,
+    remaining: u32,
+}
+
+impl<T> Tags<T>
+where
+    T: Iterator<Item = u8>,
+{
+    /// Create an iterator over the tags contained in the next `size` bytes.
+    ///
+    /// Offsets in errors are relative to the start of the given bytes.
+    pub const fn new(bytes: T, size: u32) -> Self {
+        Self::from_parser(Parser::new(bytes, None), size)
+    }
+
+    /// Create an iterator that continues parsing where the parser stopped.
+    pub(super) const fn from_parser(parser: Parser<T>, remaining: u32) -> Self {
+        Self { parser, remaining }
+    }
+
+    /// Parse the next tag, checking that it does not exceed the remaining bytes.
+    fn parse_tag(&mut self) -> Result<Tag, OtaError> {
+        let (offset, id, length) = self.parser.read_tag_header(&mut self.remaining)?;
+        let data = self.parser.read_bytes(length, Field::TagData)?;
+        Tag::new(id, data).ok_or(OtaError::Truncated {
+            offset,
+            field: Field::TagData,
+        })
+    }
+}
+
+impl<T> Iterator for Tags<T>
+where
+    T: Iterator<Item = u8>,
+{
+    type Item = Result<Tag, OtaError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let result = self.parse_tag();
+
+        if result.is_err() {
+            self.remaining = 0;
+        }
+
+        Some(result)
+    }
+}
+
+impl<T> FusedIterator for Tags<T> where T: Iterator<Item = u8> {}
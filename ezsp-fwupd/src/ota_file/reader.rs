@@ -0,0 +1,421 @@
+use std::io::{Cursor, ErrorKind, Read, Seek, SeekFrom, copy};
+use std::ops::RangeInclusive;
+
+pub use self::tag_info::TagInfo;
+pub use self::upgrade_image::UpgradeImage;
+use super::integrity_code::IntegrityCode;
+use super::parser::Parser;
+use super::{Header, OtaError, OtaFile, Tag, UpgradeFileDestination};
+use crate::FirmwareError;
+use crate::ebl::{self, Ebl};
+use crate::gbl::{self, Gbl};
+
+mod tag_info;
+mod upgrade_image;
+
+/// Streaming reader for OTA files.
+///
+/// Only the header and the tag table are parsed up front.
+/// The tags' data is read from the underlying reader on demand.
+#[derive(Debug)]
+pub struct OtaReader<R> {
+    reader: R,
+    ota_file: OtaFile,
+    tags: Vec<TagInfo>,
+}
+
+impl<R> OtaReader<R>
+where
+    R: Read + Seek,
+{
+    /// Parse the header and the tag table of the OTA file provided by the given reader.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`OtaError`] if reading fails or the data is not a valid OTA file.
+    pub fn new(mut reader: R) -> Result<Self, OtaError> {
+        let file_size = usize::try_from(reader.seek(SeekFrom::End(0))?).unwrap_or(usize::MAX);
+        reader.rewind()?;
+
+        let mut bytes = Bytes::new(&mut reader);
+        let result = Parser::new(&mut bytes, Some(file_size)).parse_header();
+
+        if let Some(error) = bytes.error {
+            return Err(error.into());
+        }
+
+        let (ota_file, remaining) = result?;
+        let tags = read_tag_table(&mut reader, u64::from(ota_file.header.length()), remaining)?;
+        Ok(Self {
+            reader,
+            ota_file,
+            tags,
+        })
+    }
+
+    /// Return the OTA file's header.
+    #[must_use]
+    pub const fn header(&self) -> &Header {
+        &self.ota_file.header
+    }
+
+    /// Return the OTA file's security credentials, if present.
+    #[must_use]
+    pub const fn security_credentials(&self) -> Option<u8> {
+        self.ota_file.security_credentials
+    }
+
+    /// Return the OTA file's upgrade file destination, if present.
+    #[must_use]
+    pub const fn upgrade_file_destination(&self) -> Option<&UpgradeFileDestination> {
+        self.ota_file.upgrade_file_destination.as_ref()
+    }
+
+    /// Return the OTA file's supported hardware versions, if present.
+    #[must_use]
+    pub const fn hardware_versions(&self) -> Option<&RangeInclusive<u16>> {
+        self.ota_file.hardware_versions.as_ref()
+    }
+
+    /// Return the OTA file's tag table.
+    #[must_use]
+    pub fn tags(&self) -> &[TagInfo] {
+        &self.tags
+    }
+
+    /// Read the data of the given tag.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`std::io::Error`] if seeking or reading fails.
+    pub fn read_tag(&mut self, tag: &TagInfo) -> std::io::Result<Tag> {
+        self.reader.seek(SeekFrom::Start(tag.offset()))?;
+        let mut data = Vec::new();
+        self.reader
+            .by_ref()
+            .take(u64::from(tag.length()))
+            .read_to_end(&mut data)?;
+        Tag::new(tag.id(), data).ok_or_else(|| ErrorKind::InvalidData.into())
+    }
+
+    /// Return a reader over the upgrade image's data, if present.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`std::io::Error`] if seeking fails.
+    pub fn upgrade_image(&mut self) -> std::io::Result<Option<UpgradeImage<'_, R>>> {
+        let Some(tag) = self
+            .tags
+            .iter()
+            .find(|tag| tag.id() == Tag::UPGRADE_IMAGE)
+            .copied()
+        else {
+            return Ok(None);
+        };
+
+        self.reader.seek(SeekFrom::Start(tag.offset()))?;
+        Ok(Some(UpgradeImage::new(&mut self.reader, tag.length())))
+    }
+
+    /// Verify the image integrity code and the CRC32 checksum of a wrapped GBL or EBL image, if present.
+    ///
+    /// This performs the same checks as loading the OTA file as [`Firmware`](crate::Firmware),
+    /// but reads the data in chunks instead of loading the entire file into memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`FirmwareError`] if reading fails or any of the checks fails.
+    pub fn verify(&mut self) -> Result<(), FirmwareError> {
+        self.verify_integrity_code()?;
+
+        let Some(mut upgrade_image) = self.upgrade_image()? else {
+            return Ok(());
+        };
+
+        let mut magic = [0; 4];
+
+        if upgrade_image.size() < 4 {
+            return Ok(());
+        }
+
+        upgrade_image.read_exact(&mut magic)?;
+        let upgrade_image = Cursor::new(magic).chain(upgrade_image);
+
+        if magic == gbl::HEADER_TAG_ID.to_le_bytes() {
+            Gbl::verify_reader(upgrade_image)
+        } else if magic == ebl::MAGIC {
+            Ebl::verify_reader(upgrade_image)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Return the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Verify the image integrity code against the hash of the preceding data, if present.
+    fn verify_integrity_code(&mut self) -> Result<(), FirmwareError> {
+        let Some(tag_info) = self
+            .tags
+            .iter()
+            .find(|tag| tag.id() == Tag::IMAGE_INTEGRITY_CODE)
+            .copied()
+        else {
+            return Ok(());
+        };
+
+        let tag = self.read_tag(&tag_info)?;
+        let (mut integrity_code, expected) =
+            IntegrityCode::for_tag(&tag).map_err(FirmwareError::InvalidOta)?;
+
+        // The hash covers all data up to and including the image integrity code's tag header.
+        self.reader.rewind()?;
+
+        if copy(
+            &mut self.reader.by_ref().take(tag_info.offset()),
+            &mut integrity_code,
+        )? != tag_info.offset()
+        {
+            return Err(std::io::Error::from(ErrorKind::UnexpectedEof).into());
+        }
+
+        integrity_code
+            .verify(expected)
+            .map_err(FirmwareError::InvalidOta)
+    }
+}
+
+/// Read the tag IDs and lengths, skipping the tags' data.
+fn read_tag_table<R>(
+    reader: &mut R,
+    mut offset: u64,
+    mut remaining: u32,
+) -> Result<Vec<TagInfo>, OtaError>
+where
+    R: Read + Seek,
+{
+    let mut tags = Vec::new();
+
+    while remaining > 0 {
+        reader.seek(SeekFrom::Start(offset))?;
+        let mut bytes = Bytes::new(reader);
+        let result = Parser::at(&mut bytes, usize::try_from(offset).unwrap_or(usize::MAX))
+            .read_tag_header(&mut remaining);
+
+        if let Some(error) = bytes.error {
+            return Err(error.into());
+        }
+
+        let (_, id, length) = result?;
+        let data_offset = offset.saturating_add(u64::from(Tag::HEADER_SIZE));
+        tags.push(TagInfo::new(id, data_offset, length));
+        offset = data_offset.saturating_add(u64::from(length));
+    }
+
+    Ok(tags)
+}
+
+/// Iterator over the bytes of a reader that stops at the first error and keeps it.
+struct Bytes<'reader, R> {
+    reader: &'reader mut R,
+    error: Option<std::io::Error>,
+}
+
+impl<'reader, R> Bytes<'reader, R> {
+    const fn new(reader: &'reader mut R) -> Self {
+        Self {
+            reader,
+            error: None,
+        }
+    }
+}
+
+impl<R> Iterator for Bytes<'_, R>
+where
+    R: Read,
+{
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut byte = [0];
+
+        match self.reader.read_exact(&mut byte) {
+            Ok(()) => Some(u8::from_le_bytes(byte)),
+            Err(error) if error.kind() == ErrorKind::UnexpectedEof => None,
+            Err(error) => {
+                self.error = Some(error);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, ErrorKind, Read, Seek, SeekFrom};
+
+    use sha2::{Digest, Sha256};
+
+    use super::OtaReader;
+    use crate::FirmwareError;
+    use crate::ebl::EblError;
+    use crate::ebl::tests::minimal_ebl;
+    use crate::gbl::GblError;
+    use crate::gbl::tests::minimal_gbl;
+    use crate::ota_file::tests::{ota_file, ota_file_with_integrity_code};
+    use crate::ota_file::{Field, OtaError, OtaFile, Tag, ValidationError, aes_mmo};
+
+    const PAYLOAD: [u8; 4] = [0xEB, 0x17, 0xA6, 0x03];
+
+    /// Reader that fails once the given position has been reached.
+    struct FailingReader {
+        cursor: Cursor<Vec<u8>>,
+        fail_at: u64,
+    }
+
+    impl Read for FailingReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.cursor.position() >= self.fail_at {
+                return Err(ErrorKind::BrokenPipe.into());
+            }
+
+            self.cursor.read(buf)
+        }
+    }
+
+    impl Seek for FailingReader {
+        fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+            self.cursor.seek(pos)
+        }
+    }
+
+    #[test]
+    fn test_read() {
+        let bytes = ota_file(&[(0xF000, &[0x01, 0x02]), (Tag::UPGRADE_IMAGE, &PAYLOAD)]);
+        let ota_file = OtaFile::try_from(bytes.as_slice()).unwrap();
+        let mut reader = OtaReader::new(Cursor::new(bytes)).unwrap();
+        assert_eq!(reader.header(), ota_file.header());
+        assert_eq!(reader.tags().len(), 2);
+        assert_eq!(reader.tags()[1].offset(), 56 + 6 + 2 + 6);
+
+        let tag = reader.tags()[0];
+        assert_eq!(reader.read_tag(&tag).unwrap(), ota_file.tags()[0]);
+
+        let mut payload = Vec::new();
+        reader
+            .upgrade_image()
+            .unwrap()
+            .unwrap()
+            .read_to_end(&mut payload)
+            .unwrap();
+        assert_eq!(Some(payload.as_slice()), ota_file.payload());
+    }
+
+    #[test]
+    fn test_no_upgrade_image() {
+        let bytes = ota_file(&[(0xF000, &[0x01, 0x02])]);
+        let mut reader = OtaReader::new(Cursor::new(bytes)).unwrap();
+        assert!(reader.upgrade_image().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_truncated_tag_header() {
+        let mut bytes = ota_file(&[(Tag::UPGRADE_IMAGE, &PAYLOAD)]);
+        bytes.truncate(56 + 3);
+        bytes[52..56].copy_from_slice(&59_u32.to_le_bytes());
+        assert!(matches!(
+            OtaReader::new(Cursor::new(bytes)),
+            Err(OtaError::Truncated {
+                offset: 58,
+                field: Field::TagLength
+            })
+        ));
+    }
+
+    #[test]
+    fn test_tag_overrun() {
+        let mut bytes = ota_file(&[(Tag::UPGRADE_IMAGE, &PAYLOAD)]);
+        bytes[58] = 5;
+        assert!(matches!(
+            OtaReader::new(Cursor::new(bytes)),
+            Err(OtaError::TagOverrun {
+                offset: 56,
+                id: Tag::UPGRADE_IMAGE,
+                length: 5,
+                remaining: 10
+            })
+        ));
+    }
+
+    #[test]
+    fn test_io_error_in_header() {
+        let reader = FailingReader {
+            cursor: Cursor::new(ota_file(&[(Tag::UPGRADE_IMAGE, &PAYLOAD)])),
+            fail_at: 20,
+        };
+        assert!(matches!(
+            OtaReader::new(reader),
+            Err(OtaError::Io(error)) if error.kind() == ErrorKind::BrokenPipe
+        ));
+    }
+
+    #[test]
+    fn test_verify_integrity_code() {
+        for bytes in [
+            ota_file_with_integrity_code(|data| aes_mmo::hash(data).to_vec(), 16),
+            ota_file_with_integrity_code(|data| Sha256::digest(data).to_vec(), 32),
+        ] {
+            let mut reader = OtaReader::new(Cursor::new(bytes)).unwrap();
+            assert!(reader.verify().is_ok());
+        }
+    }
+
+    #[test]
+    fn test_verify_integrity_code_mismatch() {
+        let mut bytes = ota_file_with_integrity_code(|data| aes_mmo::hash(data).to_vec(), 16);
+        bytes[62] ^= 0xFF;
+        let mut reader = OtaReader::new(Cursor::new(bytes)).unwrap();
+        assert!(matches!(
+            reader.verify(),
+            Err(FirmwareError::InvalidOta(
+                ValidationError::IntegrityCodeMismatch { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_verify_wrapped_gbl() {
+        let gbl = minimal_gbl();
+        let mut reader =
+            OtaReader::new(Cursor::new(ota_file(&[(Tag::UPGRADE_IMAGE, &gbl)]))).unwrap();
+        assert!(reader.verify().is_ok());
+
+        let mut corrupted = gbl;
+        corrupted[50] ^= 0xFF;
+        let mut reader =
+            OtaReader::new(Cursor::new(ota_file(&[(Tag::UPGRADE_IMAGE, &corrupted)]))).unwrap();
+        assert!(matches!(
+            reader.verify(),
+            Err(FirmwareError::Gbl(GblError::CrcMismatch { .. }))
+        ));
+    }
+
+    #[test]
+    fn test_verify_wrapped_ebl() {
+        let ebl = minimal_ebl();
+        let mut reader =
+            OtaReader::new(Cursor::new(ota_file(&[(Tag::UPGRADE_IMAGE, &ebl)]))).unwrap();
+        assert!(reader.verify().is_ok());
+
+        let mut truncated = ebl;
+        truncated.truncate(truncated.len() - 8);
+        let mut reader =
+            OtaReader::new(Cursor::new(ota_file(&[(Tag::UPGRADE_IMAGE, &truncated)]))).unwrap();
+        assert!(matches!(
+            reader.verify(),
+            Err(FirmwareError::Ebl(EblError::MissingEndTag))
+        ));
+    }
+}
@@ -0,0 +1,32 @@
+/// Location of a tag's data within an OTA file.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct TagInfo {
+    id: u16,
+    offset: u64,
+    length: u32,
+}
+
+impl TagInfo {
+    /// Create new tag information.
+    pub(super) const fn new(id: u16, offset: u64, length: u32) -> Self {
+        Self { id, offset, length }
+    }
+
+    /// Return the tag ID.
+    #[must_use]
+    pub const fn id(&self) -> u16 {
+        self.id
+    }
+
+    /// Return the offset of the tag's data from the start of the file.
+    #[must_use]
+    pub const fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Return the length of the tag's data in bytes.
+    #[must_use]
+    pub const fn length(&self) -> u32 {
+        self.length
+    }
+}
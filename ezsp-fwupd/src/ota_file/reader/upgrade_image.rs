@@ -0,0 +1,38 @@
+use std::io::{Read, Take};
+
+/// Reader over the data of an OTA file's upgrade image.
+#[derive(Debug)]
+pub struct UpgradeImage<'reader, R> {
+    reader: Take<&'reader mut R>,
+    length: u32,
+}
+
+impl<'reader, R> UpgradeImage<'reader, R>
+where
+    R: Read,
+{
+    /// Create a reader over the next `length` bytes of the given reader.
+    pub(super) fn new(reader: &'reader mut R, length: u32) -> Self {
+        Self {
+            reader: reader.take(u64::from(length)),
+            length,
+        }
+    }
+}
+
+impl<R> UpgradeImage<'_, R> {
+    /// Return the size of the upgrade image in bytes.
+    #[must_use]
+    pub const fn size(&self) -> u32 {
+        self.length
+    }
+}
+
+impl<R> Read for UpgradeImage<'_, R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.reader.read(buf)
+    }
+}
@@ -0,0 +1,218 @@
+//! Registry of Zigbee manufacturer codes and OTA image types.
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::fs::read_to_string;
+use std::path::Path;
+use std::sync::LazyLock;
+
+pub use self::error::RegistryError;
+
+mod error;
+
+/// The built-in registry data.
+const BUILTIN: &str = include_str!("registry/builtin.txt");
+
+/// The built-in registry.
+static BUILTIN_REGISTRY: LazyLock<Registry> = LazyLock::new(|| {
+    let mut registry = Registry::default();
+    let _ = registry.extend_from_str(BUILTIN);
+    registry
+});
+
+/// Maps manufacturer codes and image types to human-readable names.
+///
+/// Registries can be extended through data files, each line of which is either
+/// `manufacturer <code> <name>` or `image-type <code or *> <image type> <name>`.
+/// Codes may be given in decimal or as `0x`-prefixed hexadecimal numbers.
+/// Empty lines and lines starting with `#` are ignored.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Registry {
+    manufacturers: BTreeMap<u16, String>,
+    image_types: BTreeMap<(Option<u16>, u16), String>,
+}
+
+impl Registry {
+    /// Return the built-in registry.
+    #[must_use]
+    pub fn builtin() -> &'static Self {
+        &BUILTIN_REGISTRY
+    }
+
+    /// Return the name of the manufacturer with the given code, if known.
+    #[must_use]
+    pub fn manufacturer(&self, manufacturer_id: u16) -> Option<&str> {
+        self.manufacturers.get(&manufacturer_id).map(String::as_str)
+    }
+
+    /// Return the name of the given image type of the given manufacturer, if known.
+    ///
+    /// Manufacturer-specific entries take precedence over entries for all manufacturers.
+    #[must_use]
+    pub fn image_type(&self, manufacturer_id: u16, image_type: u16) -> Option<&str> {
+        self.image_types
+            .get(&(Some(manufacturer_id), image_type))
+            .or_else(|| self.image_types.get(&(None, image_type)))
+            .map(String::as_str)
+    }
+
+    /// Return a displayable name and hex value of the given manufacturer.
+    #[must_use]
+    pub fn display_manufacturer(&self, manufacturer_id: u16) -> impl Display {
+        Named {
+            name: self.manufacturer(manufacturer_id),
+            value: manufacturer_id,
+        }
+    }
+
+    /// Return a displayable name and hex value of the given image type.
+    #[must_use]
+    pub fn display_image_type(&self, manufacturer_id: u16, image_type: u16) -> impl Display {
+        Named {
+            name: self.image_type(manufacturer_id, image_type),
+            value: image_type,
+        }
+    }
+
+    /// Extend the registry with the entries of the given data file.
+    ///
+    /// Entries of the data file replace existing entries with the same codes.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`RegistryError`] if the file cannot be read or contains a malformed entry.
+    pub fn extend_from_file(&mut self, path: impl AsRef<Path>) -> Result<(), RegistryError> {
+        self.extend_from_str(&read_to_string(path)?)
+    }
+
+    /// Extend the registry with the entries of the given data.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`RegistryError`] if the data contains a malformed entry.
+    /// In that case, the registry is left unchanged.
+    pub fn extend_from_str(&mut self, data: &str) -> Result<(), RegistryError> {
+        let mut entries = Self::default();
+
+        for (index, line) in data.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            entries
+                .insert(line)
+                .ok_or_else(|| RegistryError::InvalidEntry(index.saturating_add(1)))?;
+        }
+
+        self.manufacturers.extend(entries.manufacturers);
+        self.image_types.extend(entries.image_types);
+        Ok(())
+    }
+
+    /// Parse and insert a single entry.
+    fn insert(&mut self, line: &str) -> Option<()> {
+        let (kind, rest) = line.split_once(char::is_whitespace)?;
+
+        match kind {
+            "manufacturer" => {
+                let (code, name) = rest.trim_start().split_once(char::is_whitespace)?;
+                self.manufacturers
+                    .insert(parse_code(code)?, name.trim().to_string());
+            }
+            "image-type" => {
+                let (code, rest) = rest.trim_start().split_once(char::is_whitespace)?;
+                let (image_type, name) = rest.trim_start().split_once(char::is_whitespace)?;
+                let manufacturer_id = if code == "*" {
+                    None
+                } else {
+                    Some(parse_code(code)?)
+                };
+                self.image_types.insert(
+                    (manufacturer_id, parse_code(image_type)?),
+                    name.trim().to_string(),
+                );
+            }
+            _ => return None,
+        }
+
+        Some(())
+    }
+}
+
+/// Displays a value by name, if known, and as hex.
+struct Named<'name> {
+    name: Option<&'name str>,
+    value: u16,
+}
+
+impl Display for Named<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.name {
+            Some(name) => write!(f, "{name} ({:#06X})", self.value),
+            None => write!(f, "Unknown ({:#06X})", self.value),
+        }
+    }
+}
+
+/// Parse a decimal or `0x`-prefixed hexadecimal code.
+fn parse_code(code: &str) -> Option<u16> {
+    code.strip_prefix("0x")
+        .or_else(|| code.strip_prefix("0X"))
+        .map_or_else(
+            || code.parse().ok(),
+            |hex| u16::from_str_radix(hex, 16).ok(),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BUILTIN, Registry, RegistryError};
+
+    #[test]
+    fn test_builtin() {
+        let mut registry = Registry::default();
+        assert!(registry.extend_from_str(BUILTIN).is_ok());
+
+        let registry = Registry::builtin();
+        assert_eq!(registry.manufacturer(0x1002), Some("Silicon Labs"));
+        assert_eq!(registry.manufacturer(0x119C), Some("Paulmann Licht"));
+        assert_eq!(registry.image_type(0x1002, 0xFFC3), Some("Picture"));
+        assert_eq!(
+            registry.display_manufacturer(0x117C).to_string(),
+            "IKEA of Sweden (0x117C)"
+        );
+        assert_eq!(
+            registry.display_image_type(0x1002, 0x0001).to_string(),
+            "Unknown (0x0001)"
+        );
+    }
+
+    #[test]
+    fn test_extend() {
+        let mut registry = Registry::builtin().clone();
+        registry
+            .extend_from_str(
+                "# Custom entries\n\
+                 manufacturer 4100 Ember\n\
+                 image-type 0x1002 0x0001 EmberZNet NCP\n\
+                 image-type * 0xFFC3 Logo\n",
+            )
+            .unwrap();
+        assert_eq!(registry.manufacturer(0x1004), Some("Ember"));
+        assert_eq!(registry.image_type(0x1002, 0x0001), Some("EmberZNet NCP"));
+        assert_eq!(registry.image_type(0x1003, 0x0001), None);
+        assert_eq!(registry.image_type(0x1002, 0xFFC3), Some("Logo"));
+    }
+
+    #[test]
+    fn test_invalid_entry() {
+        let mut registry = Registry::default();
+        assert!(matches!(
+            registry.extend_from_str("manufacturer 0x1002 Silicon Labs\nvendor 1 x"),
+            Err(RegistryError::InvalidEntry(2))
+        ));
+        assert_eq!(registry, Registry::default());
+    }
+}
@@ -0,0 +1,34 @@
+use std::fmt::Display;
+
+/// Errors that can occur when loading a [`Registry`](super::Registry) data file.
+#[derive(Debug)]
+pub enum RegistryError {
+    /// The data file could not be read.
+    Io(std::io::Error),
+    /// The entry on the given line number is malformed.
+    InvalidEntry(usize),
+}
+
+impl Display for RegistryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "I/O error: {error}"),
+            Self::InvalidEntry(line) => write!(f, "Invalid registry entry on line {line}"),
+        }
+    }
+}
+
+impl std::error::Error for RegistryError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::InvalidEntry(_) => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for RegistryError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
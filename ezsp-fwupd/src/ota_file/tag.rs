@@ -1,21 +1,97 @@
-use le_stream::FromLeStream;
+use le_stream::{FromLeStream, ToLeStream};
 
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, FromLeStream)]
+use super::tag_kind::TagKind;
+
+/// Represents a sub-element (tag) of an OTA file, including its data.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Tag {
     id: u16,
-    length: u32,
+    #[cfg_attr(feature = "serde", serde(serialize_with = "crate::hex::serialize"))]
+    data: Vec<u8>,
 }
 
 impl Tag {
-    pub const SIZE: u32 = 2 + 4;
+    /// Size of the tag header, i.e. the tag ID and the length field.
+    pub const HEADER_SIZE: u32 = 2 + 4;
+
+    /// Tag ID of the upgrade image sub-element.
+    pub const UPGRADE_IMAGE: u16 = 0x0000;
+
+    /// Tag ID of the image integrity code sub-element.
+    pub const IMAGE_INTEGRITY_CODE: u16 = 0x0003;
+
+    /// Create a new tag with the given ID and data.
+    ///
+    /// Returns `None` if the data is too large to be represented in a tag.
+    #[must_use]
+    pub fn new(id: u16, data: Vec<u8>) -> Option<Self> {
+        u32::try_from(data.len()).ok().map(|_| Self { id, data })
+    }
 
+    /// Return the tag ID.
     #[must_use]
-    pub const fn id(self) -> u16 {
+    pub const fn id(&self) -> u16 {
         self.id
     }
 
+    /// Return the length of the tag's data.
+    #[must_use]
+    #[expect(clippy::cast_possible_truncation)]
+    pub const fn length(&self) -> u32 {
+        // The length is read from a `u32` or checked on construction, so the data cannot exceed it.
+        self.data.len() as u32
+    }
+
+    /// Return the tag's data.
+    #[must_use]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Decode the tag into its typed representation.
+    ///
+    /// Returns `None` if the tag's data does not match the layout of its tag ID.
     #[must_use]
-    pub const fn length(self) -> u32 {
-        self.length
+    pub fn kind(&self) -> Option<TagKind<'_>> {
+        TagKind::decode(self)
+    }
+
+    /// Convert the tag into its data.
+    #[must_use]
+    pub fn into_data(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+impl FromLeStream for Tag {
+    fn from_le_stream<T>(mut bytes: T) -> Option<Self>
+    where
+        T: Iterator<Item = u8>,
+    {
+        let id = u16::from_le_stream(&mut bytes)?;
+        let length = usize::try_from(u32::from_le_stream(&mut bytes)?).ok()?;
+        let data: Vec<u8> = bytes.take(length).collect();
+
+        if data.len() == length {
+            Some(Self { id, data })
+        } else {
+            None
+        }
+    }
+}
+
+impl ToLeStream for Tag {
+    type Iter = std::iter::Chain<
+        std::iter::Chain<<u16 as ToLeStream>::Iter, <u32 as ToLeStream>::Iter>,
+        std::vec::IntoIter<u8>,
+    >;
+
+    fn to_le_stream(self) -> Self::Iter {
+        let length = self.length();
+        self.id
+            .to_le_stream()
+            .chain(length.to_le_stream())
+            .chain(self.data)
     }
 }
@@ -0,0 +1,195 @@
+use std::fmt::Display;
+
+use le_stream::FromLeStream;
+
+pub use self::ecdsa_certificate_163k1::EcdsaCertificate163k1;
+pub use self::ecdsa_certificate_283k1::EcdsaCertificate283k1;
+pub use self::ecdsa_signature::{EcdsaSignature, EcdsaSignature163k1, EcdsaSignature283k1};
+use super::aes_mmo;
+use super::tag::Tag;
+use crate::hex::Hex;
+
+const ECDSA_SIGNATURE_163K1: u16 = 0x0001;
+const ECDSA_SIGNING_CERTIFICATE_163K1: u16 = 0x0002;
+const PICTURE_DATA: u16 = 0x0004;
+const ECDSA_SIGNATURE_283K1: u16 = 0x0005;
+const ECDSA_SIGNING_CERTIFICATE_283K1: u16 = 0x0006;
+const MANUFACTURER_SPECIFIC_START: u16 = 0xF000;
+
+/// Size of a SHA-256 hash stored in an image integrity code sub-element.
+const SHA256_SIZE: usize = 32;
+
+mod ecdsa_certificate_163k1;
+mod ecdsa_certificate_283k1;
+mod ecdsa_signature;
+
+/// Typed representation of a Zigbee OTA sub-element.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum TagKind<'tag> {
+    /// The upgrade image to be flashed onto the device.
+    UpgradeImage(&'tag [u8]),
+    /// An ECDSA signature using the sect163k1 curve (crypto suite 1).
+    EcdsaSignature163k1(EcdsaSignature163k1),
+    /// An ECDSA signing certificate using the sect163k1 curve (crypto suite 1).
+    EcdsaSigningCertificate163k1(EcdsaCertificate163k1),
+    /// The image integrity code, i.e. an AES-MMO or SHA-256 hash of the image.
+    ImageIntegrityCode(&'tag [u8]),
+    /// Picture data.
+    PictureData(&'tag [u8]),
+    /// An ECDSA signature using the sect283k1 curve (crypto suite 2).
+    EcdsaSignature283k1(EcdsaSignature283k1),
+    /// An ECDSA signing certificate using the sect283k1 curve (crypto suite 2).
+    EcdsaSigningCertificate283k1(EcdsaCertificate283k1),
+    /// A manufacturer-specific sub-element (tag IDs `0xF000..=0xFFFF`).
+    ManufacturerSpecific {
+        /// The tag ID.
+        id: u16,
+        /// The tag's raw data.
+        data: &'tag [u8],
+    },
+    /// A sub-element with a reserved tag ID.
+    Reserved {
+        /// The tag ID.
+        id: u16,
+        /// The tag's raw data.
+        data: &'tag [u8],
+    },
+}
+
+impl<'tag> TagKind<'tag> {
+    /// Decode the given tag.
+    ///
+    /// Returns `None` if the tag's data does not match the layout of its tag ID.
+    #[must_use]
+    pub fn decode(tag: &'tag Tag) -> Option<Self> {
+        let data = tag.data();
+
+        match tag.id() {
+            Tag::UPGRADE_IMAGE => Some(Self::UpgradeImage(data)),
+            ECDSA_SIGNATURE_163K1 => decode_exact(data).map(Self::EcdsaSignature163k1),
+            ECDSA_SIGNING_CERTIFICATE_163K1 => {
+                decode_exact(data).map(Self::EcdsaSigningCertificate163k1)
+            }
+            Tag::IMAGE_INTEGRITY_CODE => matches!(data.len(), aes_mmo::SIZE | SHA256_SIZE)
+                .then_some(Self::ImageIntegrityCode(data)),
+            PICTURE_DATA => Some(Self::PictureData(data)),
+            ECDSA_SIGNATURE_283K1 => decode_exact(data).map(Self::EcdsaSignature283k1),
+            ECDSA_SIGNING_CERTIFICATE_283K1 => {
+                decode_exact(data).map(Self::EcdsaSigningCertificate283k1)
+            }
+            id @ MANUFACTURER_SPECIFIC_START.. => Some(Self::ManufacturerSpecific { id, data }),
+            id => Some(Self::Reserved { id, data }),
+        }
+    }
+}
+
+impl Display for TagKind<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UpgradeImage(data) => write!(f, "Upgrade image ({} bytes)", data.len()),
+            Self::EcdsaSignature163k1(signature) => {
+                write!(f, "ECDSA signature (sect163k1): {signature}")
+            }
+            Self::EcdsaSigningCertificate163k1(certificate) => {
+                write!(f, "ECDSA signing certificate (sect163k1): {certificate}")
+            }
+            Self::ImageIntegrityCode(hash) => {
+                write!(f, "Image integrity code: {}", Hex(hash))
+            }
+            Self::PictureData(data) => write!(f, "Picture data ({} bytes)", data.len()),
+            Self::EcdsaSignature283k1(signature) => {
+                write!(f, "ECDSA signature (sect283k1): {signature}")
+            }
+            Self::EcdsaSigningCertificate283k1(certificate) => {
+                write!(f, "ECDSA signing certificate (sect283k1): {certificate}")
+            }
+            Self::ManufacturerSpecific { id, data } => {
+                write!(f, "Manufacturer specific {id:#06X} ({} bytes)", data.len())
+            }
+            Self::Reserved { id, data } => {
+                write!(f, "Reserved {id:#06X} ({} bytes)", data.len())
+            }
+        }
+    }
+}
+
+/// Decode a value that must span the entire data.
+fn decode_exact<T>(data: &[u8]) -> Option<T>
+where
+    T: FromLeStream,
+{
+    T::from_le_stream_exact(data.iter().copied()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use le_stream::FromLeStream;
+
+    use super::{Tag, TagKind};
+
+    fn tag(id: u16, data: &[u8]) -> Tag {
+        let mut bytes = id.to_le_bytes().to_vec();
+        bytes.extend_from_slice(&u32::try_from(data.len()).unwrap().to_le_bytes());
+        bytes.extend_from_slice(data);
+        Tag::from_le_stream_exact(bytes.into_iter()).unwrap()
+    }
+
+    #[test]
+    fn test_ecdsa_signature_163k1() {
+        let mut data = vec![0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01];
+        data.extend_from_slice(&[0xAB; 42]);
+        let tag = tag(0x0001, &data);
+        let Some(TagKind::EcdsaSignature163k1(signature)) = tag.kind() else {
+            panic!("Expected ECDSA signature");
+        };
+        assert_eq!(
+            signature.signer().into_array(),
+            [0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08]
+        );
+        assert_eq!(signature.signature(), &[0xAB; 42]);
+    }
+
+    #[test]
+    fn test_ecdsa_certificate_283k1() {
+        let mut data = vec![0x00];
+        data.extend_from_slice(&[0x11; 8]);
+        data.extend_from_slice(&[0x0D, 0x08]);
+        data.extend_from_slice(&[0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08]);
+        data.extend_from_slice(&[0x00, 0x00, 0x00, 0x01, 0x00]);
+        data.extend_from_slice(&[0xFF; 4]);
+        data.extend_from_slice(&[0x0A, 0x0B, 0x0C, 0x0D, 0x0E, 0x0F, 0x10, 0x11]);
+        data.push(0x88);
+        data.extend_from_slice(&[0x02; 37]);
+        let tag = tag(0x0006, &data);
+        let Some(TagKind::EcdsaSigningCertificate283k1(certificate)) = tag.kind() else {
+            panic!("Expected ECDSA certificate");
+        };
+        assert_eq!(
+            certificate.issuer().into_array(),
+            [0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08]
+        );
+        assert_eq!(
+            certificate.subject().into_array(),
+            [0x0A, 0x0B, 0x0C, 0x0D, 0x0E, 0x0F, 0x10, 0x11]
+        );
+        assert_eq!(certificate.valid_from(), 0x0100);
+        assert_eq!(certificate.valid_to(), u32::MAX);
+        assert_eq!(certificate.key_usage(), 0x88);
+    }
+
+    #[test]
+    fn test_malformed_signature() {
+        assert_eq!(tag(0x0005, &[0x00; 79]).kind(), None);
+    }
+
+    #[test]
+    fn test_manufacturer_specific() {
+        assert_eq!(
+            tag(0xF001, &[0x42]).kind(),
+            Some(TagKind::ManufacturerSpecific {
+                id: 0xF001,
+                data: &[0x42]
+            })
+        );
+    }
+}
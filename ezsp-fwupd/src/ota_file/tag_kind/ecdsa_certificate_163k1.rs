@@ -0,0 +1,56 @@
+use std::fmt::Display;
+
+use ezsp::ember::Eui64;
+use le_stream::FromLeStream;
+
+use crate::hex::Hex;
+
+/// Represents an implicit ECQV certificate using the sect163k1 curve (crypto suite 1).
+///
+/// Other than in the rest of the OTA file, the certificate's fields are stored in big endian.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, FromLeStream)]
+pub struct EcdsaCertificate163k1 {
+    public_key_reconstruction_data: [u8; 22],
+    subject: [u8; 8],
+    issuer: [u8; 8],
+    profile_attribute_data: [u8; 10],
+}
+
+impl EcdsaCertificate163k1 {
+    /// Return the public key reconstruction data.
+    #[must_use]
+    pub const fn public_key_reconstruction_data(&self) -> &[u8; 22] {
+        &self.public_key_reconstruction_data
+    }
+
+    /// Return the subject's IEEE address.
+    #[must_use]
+    pub fn subject(&self) -> Eui64 {
+        Eui64::from(self.subject)
+    }
+
+    /// Return the issuer's IEEE address.
+    #[must_use]
+    pub fn issuer(&self) -> Eui64 {
+        Eui64::from(self.issuer)
+    }
+
+    /// Return the profile attribute data.
+    #[must_use]
+    pub const fn profile_attribute_data(&self) -> &[u8; 10] {
+        &self.profile_attribute_data
+    }
+}
+
+impl Display for EcdsaCertificate163k1 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "subject {}, issuer {}, public key reconstruction data {}, profile attribute data {}",
+            self.subject(),
+            self.issuer(),
+            Hex(&self.public_key_reconstruction_data),
+            Hex(&self.profile_attribute_data)
+        )
+    }
+}
@@ -0,0 +1,106 @@
+use std::fmt::Display;
+
+use ezsp::ember::Eui64;
+use le_stream::FromLeStream;
+
+use crate::hex::Hex;
+
+/// Represents an implicit ECQV certificate using the sect283k1 curve (crypto suite 2).
+///
+/// Other than in the rest of the OTA file, the certificate's fields are stored in big endian.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, FromLeStream)]
+pub struct EcdsaCertificate283k1 {
+    certificate_type: u8,
+    serial_number: [u8; 8],
+    curve: u8,
+    hash: u8,
+    issuer: [u8; 8],
+    valid_from: [u8; 5],
+    valid_to: [u8; 4],
+    subject: [u8; 8],
+    key_usage: u8,
+    public_key: [u8; 37],
+}
+
+impl EcdsaCertificate283k1 {
+    /// Return the certificate type.
+    #[must_use]
+    pub const fn certificate_type(&self) -> u8 {
+        self.certificate_type
+    }
+
+    /// Return the serial number.
+    #[must_use]
+    pub const fn serial_number(&self) -> &[u8; 8] {
+        &self.serial_number
+    }
+
+    /// Return the curve identifier.
+    #[must_use]
+    pub const fn curve(&self) -> u8 {
+        self.curve
+    }
+
+    /// Return the hash algorithm identifier.
+    #[must_use]
+    pub const fn hash(&self) -> u8 {
+        self.hash
+    }
+
+    /// Return the issuer's IEEE address.
+    #[must_use]
+    pub fn issuer(&self) -> Eui64 {
+        Eui64::from(self.issuer)
+    }
+
+    /// Return the start of the validity period in seconds since 2000-01-01 00:00:00 UTC.
+    #[must_use]
+    pub fn valid_from(&self) -> u64 {
+        self.valid_from
+            .iter()
+            .fold(0, |value, &byte| (value << 8) | u64::from(byte))
+    }
+
+    /// Return the validity period in seconds, starting from [`Self::valid_from`].
+    #[must_use]
+    pub const fn valid_to(&self) -> u32 {
+        u32::from_be_bytes(self.valid_to)
+    }
+
+    /// Return the subject's IEEE address.
+    #[must_use]
+    pub fn subject(&self) -> Eui64 {
+        Eui64::from(self.subject)
+    }
+
+    /// Return the key usage flags.
+    #[must_use]
+    pub const fn key_usage(&self) -> u8 {
+        self.key_usage
+    }
+
+    /// Return the compressed public key.
+    #[must_use]
+    pub const fn public_key(&self) -> &[u8; 37] {
+        &self.public_key
+    }
+}
+
+impl Display for EcdsaCertificate283k1 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "type {:#04X}, serial {}, curve {:#04X}, hash {:#04X}, issuer {}, valid from {}, valid to {}, subject {}, key usage {:#04X}, public key {}",
+            self.certificate_type,
+            Hex(&self.serial_number),
+            self.curve,
+            self.hash,
+            self.issuer(),
+            self.valid_from(),
+            self.valid_to(),
+            self.subject(),
+            self.key_usage,
+            Hex(&self.public_key)
+        )
+    }
+}
@@ -0,0 +1,56 @@
+use std::fmt::Display;
+
+use ezsp::ember::Eui64;
+use le_stream::FromLeStream;
+
+use crate::hex::Hex;
+
+/// ECDSA signature using the sect163k1 curve (crypto suite 1).
+pub type EcdsaSignature163k1 = EcdsaSignature<42>;
+
+/// ECDSA signature using the sect283k1 curve (crypto suite 2).
+pub type EcdsaSignature283k1 = EcdsaSignature<72>;
+
+/// Represents an ECDSA signature sub-element of an OTA file.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct EcdsaSignature<const SIZE: usize> {
+    signer: Eui64,
+    signature: [u8; SIZE],
+}
+
+impl<const SIZE: usize> EcdsaSignature<SIZE> {
+    /// Return the IEEE address of the signer.
+    #[must_use]
+    pub const fn signer(&self) -> Eui64 {
+        self.signer
+    }
+
+    /// Return the signature, i.e. the concatenated `r` and `s` values.
+    #[must_use]
+    pub const fn signature(&self) -> &[u8; SIZE] {
+        &self.signature
+    }
+}
+
+impl<const SIZE: usize> Display for EcdsaSignature<SIZE> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "signer {}, signature {}",
+            self.signer,
+            Hex(&self.signature)
+        )
+    }
+}
+
+impl<const SIZE: usize> FromLeStream for EcdsaSignature<SIZE> {
+    fn from_le_stream<T>(mut bytes: T) -> Option<Self>
+    where
+        T: Iterator<Item = u8>,
+    {
+        Some(Self {
+            signer: Eui64::from_le_stream(&mut bytes)?,
+            signature: <[u8; SIZE]>::from_le_stream(&mut bytes)?,
+        })
+    }
+}
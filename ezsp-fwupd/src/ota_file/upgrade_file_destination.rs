@@ -1,26 +1,78 @@
 use std::fmt::Display;
 
 use ezsp::ember::Eui64;
+use le_stream::ToLeStream;
+
+use super::HeaderVersion;
+use crate::hex::Hex;
 
 /// Represents a Thread device identifier (Thread ID).
 pub type ThreadId = [u8; 32];
 
 /// Represents the destination for an OTA (Over-The-Air) upgrade file.
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum UpgradeFileDestination {
     /// Destination for a Zigbee device, identified by its EUI-64 address.
-    Zigbee(Eui64),
+    Zigbee(#[cfg_attr(feature = "serde", serde(serialize_with = "serialize_eui64"))] Eui64),
     /// Destination for a Thread device, identified by its Thread ID.
-    Thread(Box<ThreadId>),
+    Thread(
+        #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_thread_id"))] Box<ThreadId>,
+    ),
+}
+
+impl UpgradeFileDestination {
+    /// Return the header version that this destination belongs to.
+    #[must_use]
+    pub const fn header_version(&self) -> HeaderVersion {
+        match self {
+            Self::Zigbee(_) => HeaderVersion::Zigbee,
+            Self::Thread(_) => HeaderVersion::Thread,
+        }
+    }
+
+    /// Return the size of the destination in bytes.
+    #[must_use]
+    pub const fn size(&self) -> u16 {
+        self.header_version().destination_size()
+    }
 }
 
 impl Display for UpgradeFileDestination {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Zigbee(eui64) => write!(f, "Zigbee: {eui64}"),
-            Self::Thread(thread_id) => {
-                write!(f, "Thread: {thread_id:010X?}")
-            }
+            Self::Thread(thread_id) => write!(f, "Thread: {}", Hex(thread_id.as_slice())),
+        }
+    }
+}
+
+/// Serialize an EUI-64 address using its display representation.
+#[cfg(feature = "serde")]
+#[expect(clippy::trivially_copy_pass_by_ref)]
+fn serialize_eui64<S>(eui64: &Eui64, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.collect_str(eui64)
+}
+
+/// Serialize a Thread ID as a string of hex digits.
+#[cfg(feature = "serde")]
+fn serialize_thread_id<S>(thread_id: &ThreadId, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.collect_str(&Hex(thread_id))
+}
+
+impl ToLeStream for UpgradeFileDestination {
+    type Iter = std::vec::IntoIter<u8>;
+
+    fn to_le_stream(self) -> Self::Iter {
+        match self {
+            Self::Zigbee(eui64) => eui64.to_le_stream().collect::<Vec<_>>().into_iter(),
+            Self::Thread(thread_id) => Vec::from(*thread_id).into_iter(),
         }
     }
 }
@@ -0,0 +1,42 @@
+use std::fmt::Display;
+
+use super::Magic;
+use crate::hex::Hex;
+
+/// Errors that can occur when validating an [`OtaFile`](super::OtaFile).
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ValidationError {
+    /// The OTA file's magic number does not match.
+    InvalidMagic(Magic),
+    /// The image integrity code sub-element has an unsupported size.
+    InvalidIntegrityCode(usize),
+    /// The hash calculated over the OTA file does not match its image integrity code.
+    IntegrityCodeMismatch {
+        /// The hash stored in the image integrity code sub-element.
+        expected: Vec<u8>,
+        /// The hash calculated over the OTA file.
+        calculated: Vec<u8>,
+    },
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidMagic(magic) => write!(f, "Invalid OTA file magic: {magic:#04X?}"),
+            Self::InvalidIntegrityCode(size) => {
+                write!(f, "Unsupported image integrity code size: {size} bytes")
+            }
+            Self::IntegrityCodeMismatch {
+                expected,
+                calculated,
+            } => write!(
+                f,
+                "Image integrity code mismatch: expected {}, calculated {}",
+                Hex(expected),
+                Hex(calculated)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
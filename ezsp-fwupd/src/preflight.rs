@@ -0,0 +1,178 @@
+use std::io;
+use std::ops::RangeInclusive;
+
+use ezsp::ember::Eui64;
+
+pub use self::run::RunPreflight;
+use crate::Firmware;
+use crate::ota_file::{OtaReader, UpgradeFileDestination};
+
+mod run;
+
+/// Requirements that a device must meet before a firmware image may be flashed onto it.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Preflight {
+    hardware_versions: Option<RangeInclusive<u16>>,
+    destination: Option<UpgradeFileDestination>,
+}
+
+impl Preflight {
+    /// Create new preflight requirements.
+    #[must_use]
+    pub const fn new(
+        hardware_versions: Option<RangeInclusive<u16>>,
+        destination: Option<UpgradeFileDestination>,
+    ) -> Self {
+        Self {
+            hardware_versions,
+            destination,
+        }
+    }
+
+    /// Create the preflight requirements stated by the given firmware image.
+    #[must_use]
+    pub fn from_firmware(firmware: &Firmware) -> Self {
+        match firmware {
+            Firmware::Ota(ota_file) => Self::new(
+                ota_file.hardware_versions().cloned(),
+                ota_file.upgrade_file_destination().cloned(),
+            ),
+            Firmware::Gbl(_) | Firmware::Ebl(_) => Self::default(),
+        }
+    }
+
+    /// Create the preflight requirements stated by the given streamed OTA file.
+    #[must_use]
+    pub fn from_ota_reader<R>(ota_reader: &OtaReader<R>) -> Self
+    where
+        R: io::Read + io::Seek,
+    {
+        Self::new(
+            ota_reader.hardware_versions().cloned(),
+            ota_reader.upgrade_file_destination().cloned(),
+        )
+    }
+
+    /// Return the range of supported hardware versions, if restricted.
+    #[must_use]
+    pub const fn hardware_versions(&self) -> Option<&RangeInclusive<u16>> {
+        self.hardware_versions.as_ref()
+    }
+
+    /// Return the device that the image is destined for, if restricted.
+    #[must_use]
+    pub const fn destination(&self) -> Option<&UpgradeFileDestination> {
+        self.destination.as_ref()
+    }
+
+    /// Drop the hardware version requirement.
+    #[must_use]
+    pub fn without_hardware_versions(self) -> Self {
+        Self {
+            hardware_versions: None,
+            ..self
+        }
+    }
+
+    /// Return whether there are no requirements to check.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.hardware_versions.is_none() && self.destination.is_none()
+    }
+
+    /// Check the given hardware version against the requirements.
+    ///
+    /// If the image restricts the hardware versions, but the device does not report one, the check fails.
+    fn check_hardware_version(&self, hardware_version: Option<u16>) -> io::Result<()> {
+        let Some(hardware_versions) = &self.hardware_versions else {
+            return Ok(());
+        };
+
+        let Some(hardware_version) = hardware_version else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Image supports hardware versions {:#06X} to {:#06X}, but device does not report a hardware version \
+                     (CustomVersion manufacturing token is not set), use --ignore-hardware-version to skip this check",
+                    hardware_versions.start(),
+                    hardware_versions.end()
+                ),
+            ));
+        };
+
+        if hardware_versions.contains(&hardware_version) {
+            return Ok(());
+        }
+
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "Image supports hardware versions {:#06X} to {:#06X}, but device has hardware version {hardware_version:#06X}",
+                hardware_versions.start(),
+                hardware_versions.end()
+            ),
+        ))
+    }
+
+    /// Check the given EUI64 against the requirements.
+    fn check_eui64(&self, eui64: Eui64) -> io::Result<()> {
+        match &self.destination {
+            None => Ok(()),
+            Some(UpgradeFileDestination::Zigbee(destination)) if *destination == eui64 => Ok(()),
+            Some(UpgradeFileDestination::Zigbee(destination)) => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Image is destined for device {destination}, but device is {eui64}"),
+            )),
+            Some(destination @ UpgradeFileDestination::Thread(_)) => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Image is destined for a Thread device ({destination}), but device is {eui64}"
+                ),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ezsp::ember::Eui64;
+
+    use super::Preflight;
+    use crate::ota_file::UpgradeFileDestination;
+
+    const EUI64: [u8; 8] = [0x00, 0x0D, 0x6F, 0x00, 0x0A, 0x1B, 0x2C, 0x3D];
+
+    #[test]
+    fn test_hardware_version() {
+        let preflight = Preflight::new(Some(0x0002..=0x0004), None);
+        assert!(preflight.check_hardware_version(Some(0x0002)).is_ok());
+        assert!(preflight.check_hardware_version(Some(0x0004)).is_ok());
+        assert!(preflight.check_hardware_version(None).is_err());
+        assert!(Preflight::default().check_hardware_version(None).is_ok());
+        assert!(preflight.check_hardware_version(Some(0x0001)).is_err());
+        assert!(preflight.check_hardware_version(Some(0x0005)).is_err());
+        assert!(
+            preflight
+                .without_hardware_versions()
+                .check_hardware_version(Some(0x0001))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_eui64() {
+        let eui64 = Eui64::from(EUI64);
+        let preflight = Preflight::new(None, Some(UpgradeFileDestination::Zigbee(eui64)));
+        assert!(preflight.check_eui64(eui64).is_ok());
+        assert!(preflight.check_eui64(Eui64::from([0; 8])).is_err());
+        assert!(Preflight::default().check_eui64(eui64).is_ok());
+        assert!(
+            Preflight::new(
+                None,
+                Some(UpgradeFileDestination::Thread(Box::new([0; 32])))
+            )
+            .check_eui64(eui64)
+            .is_err()
+        );
+    }
+}
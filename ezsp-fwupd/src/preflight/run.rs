@@ -0,0 +1,107 @@
+use std::io;
+
+use ashv2::TryCloneNative;
+use ezsp::Utilities;
+use ezsp::ezsp::mfg_token::Id;
+use ezsp::uart::Uart;
+use log::{debug, info};
+use serialport::SerialPort;
+
+use super::Preflight;
+use crate::{UartParams, make_uart};
+
+const UNSET: u16 = 0xFFFF;
+
+/// Check a Zigbee NCP against the [`Preflight`] requirements of a firmware image.
+pub trait RunPreflight: Sized {
+    /// Query the NCP over EZSP using the given UART parameters and check it against the given requirements.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`io::Error`] if the NCP cannot be queried or does not meet the requirements.
+    fn preflight(
+        self,
+        preflight: &Preflight,
+        uart_params: &UartParams,
+    ) -> impl Future<Output = io::Result<Self>>;
+}
+
+impl<T> RunPreflight for T
+where
+    T: SerialPort + TryCloneNative + Send + Sync + 'static,
+{
+    async fn preflight(self, preflight: &Preflight, uart_params: &UartParams) -> io::Result<Self> {
+        if preflight.is_empty() {
+            return Ok(self);
+        }
+
+        let (tasks, mut uart) = make_uart(
+            self,
+            uart_params.callback_channel_size(),
+            uart_params.response_channel_size(),
+            uart_params.protocol_version(),
+        )?;
+        let result = check(&mut uart, preflight).await;
+        let serial_port = tasks.terminate().await.map_err(|error| {
+            io::Error::other(format!("Failed to terminate actor tasks: {error}"))
+        })?;
+        result.map(|()| serial_port)
+    }
+}
+
+/// Check the NCP behind the given UART against the requirements.
+async fn check(uart: &mut Uart, preflight: &Preflight) -> io::Result<()> {
+    if preflight.hardware_versions().is_some() {
+        let hardware_version = read_hardware_version(uart).await?;
+
+        if let Some(hardware_version) = hardware_version {
+            info!("Device hardware version: {hardware_version:#06X}");
+        }
+
+        preflight.check_hardware_version(hardware_version)?;
+    }
+
+    if preflight.destination().is_some() {
+        let eui64 = uart
+            .get_eui64()
+            .await
+            .map_err(|error| io::Error::other(format!("Failed to read device EUI64: {error}")))?;
+        info!("Device EUI64: {eui64}");
+        preflight.check_eui64(eui64)?;
+    }
+
+    Ok(())
+}
+
+/// Read the NCP's board name and hardware version from its manufacturing tokens.
+///
+/// The hardware version is stored in the custom version token.
+/// Returns `Ok(None)` if the token is not set.
+async fn read_hardware_version(uart: &mut Uart) -> io::Result<Option<u16>> {
+    match uart.get_mfg_token(Id::BoardName).await {
+        Ok(board_name) => info!(
+            "Device board name: {}",
+            String::from_utf8_lossy(&board_name).trim_end_matches(['\0', '\u{FFFD}'])
+        ),
+        Err(error) => debug!("Failed to read board name: {error}"),
+    }
+
+    let token = uart
+        .get_mfg_token(Id::CustomVersion)
+        .await
+        .map_err(|error| {
+            io::Error::other(format!("Failed to read device hardware version: {error}"))
+        })?;
+
+    let &[low, high, ..] = token.as_slice() else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Invalid hardware version token: {token:#04X?}"),
+        ));
+    };
+
+    match u16::from_le_bytes([low, high]) {
+        UNSET => Ok(None),
+        hardware_version => Ok(Some(hardware_version)),
+    }
+}
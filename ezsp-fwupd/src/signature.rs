@@ -0,0 +1,24 @@
+//! ECDSA signature verification of firmware images.
+
+pub use self::error::SignatureError;
+pub use self::public_key::{CryptoSuite, PublicKey};
+
+mod binary_curve;
+mod error;
+mod public_key;
+
+#[cfg(test)]
+pub mod tests {
+    use num_bigint::BigUint;
+
+    use super::binary_curve::SECT163K1;
+
+    /// Return the public key and the signature `r || s` over the hash for a fixed sect163k1 test key.
+    pub fn sign_sect163k1(hash: &[u8]) -> (Vec<u8>, Vec<u8>) {
+        let private_key = BigUint::from(0xC0FF_EE00_1234_u64);
+        (
+            SECT163K1.public_key(&private_key),
+            SECT163K1.sign(&private_key, &BigUint::from(0xBEEF_u16), hash),
+        )
+    }
+}
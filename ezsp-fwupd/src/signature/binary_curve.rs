@@ -0,0 +1,489 @@
+//! Elliptic curves over binary fields as used by the Zigbee Smart Energy crypto suites.
+//!
+//! This implementation only verifies public data and is therefore not hardened against side channels.
+
+use std::mem::swap;
+use std::sync::LazyLock;
+
+use num_bigint::BigUint;
+
+/// The sect163k1 curve used by Zigbee crypto suite 1.
+pub static SECT163K1: LazyLock<Curve> = LazyLock::new(|| {
+    Curve::new(
+        163,
+        &[163, 7, 6, 3, 0],
+        1,
+        "02FE13C0537BBC11ACAA07D793DE4E6D5E5C94EEE8",
+        "0289070FB05D38FF58321F2E800536D538CCDAA3D9",
+        "04000000000000000000020108A2E0CC0D99F8A5EF",
+    )
+});
+
+/// The sect283k1 curve used by Zigbee crypto suite 2.
+pub static SECT283K1: LazyLock<Curve> = LazyLock::new(|| {
+    Curve::new(
+        283,
+        &[283, 12, 7, 5, 0],
+        0,
+        "0503213F78CA44883F1A3B8162F188E553CD265F23C1567A16876913B0C2AC2458492836",
+        "01CCDA380F1C9E318D90F95D07E5426FE87E45C0E8184698E45962364E34116177DD2259",
+        "01FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFE9AE2ED07577265DFF7F94451E061E163C61",
+    )
+});
+
+/// An affine point on a binary curve other than the point at infinity.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Point {
+    x: BigUint,
+    y: BigUint,
+}
+
+/// A Koblitz curve `y² + xy = x³ + ax² + 1` over `GF(2^m)`.
+#[derive(Debug)]
+pub struct Curve {
+    degree: u64,
+    polynomial: BigUint,
+    a: BigUint,
+    generator: Point,
+    order: BigUint,
+}
+
+impl Curve {
+    /// Create a curve from its SEC 2 domain parameters.
+    fn new(degree: u64, exponents: &[u64], a: u8, gx: &str, gy: &str, order: &str) -> Self {
+        let mut polynomial = BigUint::ZERO;
+        for &exponent in exponents {
+            polynomial.set_bit(exponent, true);
+        }
+
+        Self {
+            degree,
+            polynomial,
+            a: BigUint::from(a),
+            generator: Point {
+                x: BigUint::parse_bytes(gx.as_bytes(), 16).unwrap_or_default(),
+                y: BigUint::parse_bytes(gy.as_bytes(), 16).unwrap_or_default(),
+            },
+            order: BigUint::parse_bytes(order.as_bytes(), 16).unwrap_or_default(),
+        }
+    }
+
+    /// Return the size of a field element in bytes.
+    pub fn element_size(&self) -> usize {
+        usize::try_from(self.degree.div_ceil(8)).unwrap_or(usize::MAX)
+    }
+
+    /// Return the size of a scalar modulo the curve's order in bytes.
+    pub fn scalar_size(&self) -> usize {
+        usize::try_from(self.order.bits().div_ceil(8)).unwrap_or(usize::MAX)
+    }
+
+    /// Decode a compressed or uncompressed SEC 1 point.
+    ///
+    /// Returns `None` if the encoding is invalid or the point is not on the curve.
+    pub fn decode_point(&self, bytes: &[u8]) -> Option<Point> {
+        let (&prefix, coordinates) = bytes.split_first()?;
+        let size = self.element_size();
+
+        let point = match prefix {
+            0x02 | 0x03 if coordinates.len() == size => {
+                self.decompress(BigUint::from_bytes_be(coordinates), prefix & 0x01 != 0)?
+            }
+            0x04 if coordinates.len() == 2 * size => {
+                let (x, y) = coordinates.split_at(size);
+                Point {
+                    x: BigUint::from_bytes_be(x),
+                    y: BigUint::from_bytes_be(y),
+                }
+            }
+            _ => return None,
+        };
+
+        self.contains(&point).then_some(point)
+    }
+
+    /// Verify an ECDSA signature over the given hash as per SEC 1, section 4.1.4.
+    pub fn verify(&self, key: &Point, hash: &[u8], r: &[u8], s: &[u8]) -> bool {
+        let r = BigUint::from_bytes_be(r);
+        let s = BigUint::from_bytes_be(s);
+
+        if !self.is_scalar(&r) || !self.is_scalar(&s) {
+            return false;
+        }
+
+        let w = self.invert_scalar(&s);
+        let u1 = (self.hash_to_scalar(hash) * &w) % &self.order;
+        let u2 = (&r * &w) % &self.order;
+
+        self.add(
+            self.multiply(Some(&self.generator), &u1).as_ref(),
+            self.multiply(Some(key), &u2).as_ref(),
+        )
+        .is_some_and(|point| point.x % &self.order == r)
+    }
+
+    /// Reconstruct a public key from an implicit ECQV certificate as per SEC 4, section 3.5.
+    ///
+    /// The public key is calculated as `e * P + Q`, where `e` is the certificate's hash,
+    /// `P` the public key reconstruction data and `Q` the certificate authority's public key.
+    pub fn reconstruct(
+        &self,
+        reconstruction_data: &[u8],
+        certificate_hash: &[u8],
+        authority: &Point,
+    ) -> Option<Point> {
+        let reconstruction_point = self.decode_point(reconstruction_data)?;
+        self.add(
+            self.multiply(
+                Some(&reconstruction_point),
+                &self.hash_to_scalar(certificate_hash),
+            )
+            .as_ref(),
+            Some(authority),
+        )
+    }
+
+    /// Sign the hash with the given private key and nonce, returning `r || s`.
+    #[cfg(test)]
+    pub fn sign(&self, private_key: &BigUint, nonce: &BigUint, hash: &[u8]) -> Vec<u8> {
+        let r = self
+            .multiply(Some(&self.generator), nonce)
+            .map(|point| point.x % &self.order)
+            .unwrap_or_default();
+        let s = (self.invert_scalar(nonce) * (self.hash_to_scalar(hash) + &r * private_key))
+            % &self.order;
+        [r, s]
+            .iter()
+            .flat_map(|value| pad(value, self.scalar_size()))
+            .collect()
+    }
+
+    /// Return the uncompressed SEC 1 encoding of the public key for the given private key.
+    #[cfg(test)]
+    pub fn public_key(&self, private_key: &BigUint) -> Vec<u8> {
+        let point = self
+            .multiply(Some(&self.generator), private_key)
+            .unwrap_or_else(|| self.generator.clone());
+        let mut bytes = vec![0x04];
+        bytes.extend(pad(&point.x, self.element_size()));
+        bytes.extend(pad(&point.y, self.element_size()));
+        bytes
+    }
+
+    /// Returns whether the point satisfies the curve equation.
+    fn contains(&self, point: &Point) -> bool {
+        if point.x.bits() > self.degree || point.y.bits() > self.degree {
+            return false;
+        }
+
+        let x2 = self.square(&point.x);
+        let lhs = self.square(&point.y) ^ self.multiply_elements(&point.x, &point.y);
+        let rhs = self.multiply_elements(&x2, &point.x)
+            ^ self.multiply_elements(&self.a, &x2)
+            ^ BigUint::from(1_u8);
+        lhs == rhs
+    }
+
+    /// Recover the y coordinate of a compressed point by solving `z² + z = x + a + 1 / x²`.
+    fn decompress(&self, x: BigUint, y_bit: bool) -> Option<Point> {
+        if x.bits() > self.degree {
+            return None;
+        }
+
+        if x == BigUint::ZERO {
+            // With b = 1, the square root of b is 1.
+            return Some(Point {
+                x,
+                y: BigUint::from(1_u8),
+            });
+        }
+
+        let x2 = self.square(&x);
+        let beta = &x ^ &self.a ^ self.invert(&x2)?;
+        let mut z = self.half_trace(&beta);
+
+        if (self.square(&z) ^ &z) != beta {
+            return None;
+        }
+
+        if z.bit(0) != y_bit {
+            z ^= BigUint::from(1_u8);
+        }
+
+        let y = self.multiply_elements(&x, &z);
+        Some(Point { x, y })
+    }
+
+    /// Add two points, where `None` represents the point at infinity.
+    fn add(&self, p: Option<&Point>, q: Option<&Point>) -> Option<Point> {
+        let (p, q) = match (p, q) {
+            (None, None) => return None,
+            (Some(point), None) | (None, Some(point)) => return Some(point.clone()),
+            (Some(p), Some(q)) => (p, q),
+        };
+
+        if p.x == q.x {
+            return if p.y == q.y {
+                self.double(p)
+            } else {
+                // q is the negation of p.
+                None
+            };
+        }
+
+        let lambda = self.multiply_elements(&(&p.y ^ &q.y), &self.invert(&(&p.x ^ &q.x))?);
+        let x = self.square(&lambda) ^ &lambda ^ &p.x ^ &q.x ^ &self.a;
+        let y = self.multiply_elements(&lambda, &(&p.x ^ &x)) ^ &x ^ &p.y;
+        Some(Point { x, y })
+    }
+
+    /// Double a point.
+    fn double(&self, p: &Point) -> Option<Point> {
+        let lambda = &p.x ^ self.multiply_elements(&p.y, &self.invert(&p.x)?);
+        let x = self.square(&lambda) ^ &lambda ^ &self.a;
+        let y = self.square(&p.x) ^ self.multiply_elements(&(lambda ^ BigUint::from(1_u8)), &x);
+        Some(Point { x, y })
+    }
+
+    /// Multiply a point by a scalar using double-and-add.
+    fn multiply(&self, point: Option<&Point>, scalar: &BigUint) -> Option<Point> {
+        let point = point?;
+        let mut result = None;
+
+        for bit in (0..scalar.bits()).rev() {
+            result = result.and_then(|result| self.double(&result));
+
+            if scalar.bit(bit) {
+                result = self.add(result.as_ref(), Some(point));
+            }
+        }
+
+        result
+    }
+
+    /// Multiply two field elements.
+    fn multiply_elements(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        let mut product = BigUint::ZERO;
+
+        for bit in (0..b.bits()).filter(|&bit| b.bit(bit)) {
+            product ^= a << bit;
+        }
+
+        self.reduce(product)
+    }
+
+    /// Square a field element.
+    fn square(&self, a: &BigUint) -> BigUint {
+        self.multiply_elements(a, a)
+    }
+
+    /// Invert a field element using the extended Euclidean algorithm for polynomials.
+    ///
+    /// Returns `None` if the element is zero.
+    fn invert(&self, a: &BigUint) -> Option<BigUint> {
+        let one = BigUint::from(1_u8);
+        let mut u = self.reduce(a.clone());
+        let mut v = self.polynomial.clone();
+        let mut g1 = one.clone();
+        let mut g2 = BigUint::ZERO;
+
+        while u != one {
+            if u == BigUint::ZERO {
+                return None;
+            }
+
+            if u.bits() < v.bits() {
+                swap(&mut u, &mut v);
+                swap(&mut g1, &mut g2);
+            }
+
+            let shift = u.bits() - v.bits();
+            u ^= &v << shift;
+            g1 ^= &g2 << shift;
+        }
+
+        Some(self.reduce(g1))
+    }
+
+    /// Reduce a polynomial modulo the field polynomial.
+    fn reduce(&self, mut a: BigUint) -> BigUint {
+        while a.bits() > self.degree {
+            a ^= &self.polynomial << (a.bits() - 1 - self.degree);
+        }
+
+        a
+    }
+
+    /// Calculate the half trace, which solves `z² + z = c` for odd field degrees.
+    fn half_trace(&self, c: &BigUint) -> BigUint {
+        let mut term = c.clone();
+        let mut half_trace = c.clone();
+
+        for _ in 0..(self.degree - 1) / 2 {
+            term = self.square(&self.square(&term));
+            half_trace ^= &term;
+        }
+
+        half_trace
+    }
+
+    /// Convert a hash into a scalar, truncating it to the bit length of the curve's order.
+    fn hash_to_scalar(&self, hash: &[u8]) -> BigUint {
+        let scalar = BigUint::from_bytes_be(hash);
+        let bits = u64::try_from(hash.len()).unwrap_or(u64::MAX) * 8;
+        scalar >> bits.saturating_sub(self.order.bits())
+    }
+
+    /// Returns whether the value is a valid non-zero scalar modulo the curve's order.
+    fn is_scalar(&self, value: &BigUint) -> bool {
+        *value != BigUint::ZERO && *value < self.order
+    }
+
+    /// Invert a scalar modulo the curve's prime order.
+    fn invert_scalar(&self, value: &BigUint) -> BigUint {
+        value.modpow(&(&self.order - 2_u8), &self.order)
+    }
+}
+
+/// Encode the value in big endian, padded to the given size.
+#[cfg(test)]
+fn pad(value: &BigUint, size: usize) -> Vec<u8> {
+    let bytes = value.to_bytes_be();
+    let mut padded = vec![0; size.saturating_sub(bytes.len())];
+    padded.extend(bytes);
+    padded
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+
+    use super::{Curve, SECT163K1, SECT283K1, pad};
+
+    /// Known-answer vectors generated with OpenSSL 3.5 from fixed private keys.
+    ///
+    /// The ECDSA key is `0x0123456789ABCDEF0123456789ABCDEF`.
+    /// The ECQV certificate authority's key is `q = 0x5EC4CA5EC4CA5EC4CA5EC4CA5EC4CA`,
+    /// the reconstruction data is `P = p * G` with `p = 0x9E7A11DA7A9E7A11DA7A9E7A11DA7A`
+    /// and the signer's key is `e * p + q` with the certificate hash `e`.
+    struct KnownAnswer {
+        curve: &'static Curve,
+        key: &'static str,
+        signature: &'static str,
+        authority: &'static str,
+        reconstruction_data: &'static str,
+        signer: &'static str,
+        signer_signature: &'static str,
+    }
+
+    const HASH: &str = "101112131415161718191A1B1C1D1E1F";
+    const CERTIFICATE_HASH: &str = "A0A1A2A3A4A5A6A7A8A9AAABACADAEAF";
+
+    fn bytes(hex: &str) -> Vec<u8> {
+        pad(
+            &BigUint::parse_bytes(hex.as_bytes(), 16).unwrap(),
+            hex.len() / 2,
+        )
+    }
+
+    fn assert_known_answer(known_answer: &KnownAnswer) {
+        let curve = known_answer.curve;
+        let hash = bytes(HASH);
+
+        let key = curve.decode_point(&bytes(known_answer.key)).unwrap();
+        let signature = bytes(known_answer.signature);
+        let (r, s) = signature.split_at(curve.scalar_size());
+        assert!(curve.verify(&key, &hash, r, s));
+        assert!(!curve.verify(&key, &bytes(CERTIFICATE_HASH), r, s));
+
+        let authority = curve.decode_point(&bytes(known_answer.authority)).unwrap();
+        let signer = curve
+            .reconstruct(
+                &bytes(known_answer.reconstruction_data),
+                &bytes(CERTIFICATE_HASH),
+                &authority,
+            )
+            .unwrap();
+        assert_eq!(
+            Some(&signer),
+            curve.decode_point(&bytes(known_answer.signer)).as_ref()
+        );
+
+        let signature = bytes(known_answer.signer_signature);
+        let (r, s) = signature.split_at(curve.scalar_size());
+        assert!(curve.verify(&signer, &hash, r, s));
+        assert!(!curve.verify(&authority, &hash, r, s));
+    }
+
+    fn assert_valid(curve: &Curve) {
+        assert!(curve.contains(&curve.generator));
+        assert_eq!(curve.multiply(Some(&curve.generator), &curve.order), None);
+    }
+
+    #[test]
+    fn test_sect163k1() {
+        assert_valid(&SECT163K1);
+    }
+
+    #[test]
+    fn test_sect163k1_known_answer() {
+        assert_known_answer(&KnownAnswer {
+            curve: &SECT163K1,
+            key: "0403E1A379FD66D6CD4285E7687DFBE3F6426F77F90600E8AC25C3E4422FA1D5A0054E04B3CD3A9ADEAE1B",
+            signature: "0385FE8C2E8E0DA3362EA663F29B8E617F5B40E83301FB476C474AECDBAAB440A13083DFD54DA3EAE88B",
+            authority: "0404618B8F1C71C7E6B5AE2E2C37A45A3657AFEA2EBE02F4C14A7A0E69CFE8EF8FE779A2CDF71F3A890065",
+            reconstruction_data: "02029363A39B92A460D5D765362B4F3926845730A6D7",
+            signer: "0404373A27A3B654117DDD7C87257AE630823F2967F801DBAD37829E4B6D9EA9530C85F6C444E73962E346",
+            signer_signature: "036E3851A1FF3ACF85457814C2A816C28DBFE9FA4602995F6695EF2466DC57D208FBB5AF3A6EC5E4DEE7",
+        });
+    }
+
+    #[test]
+    fn test_sect283k1() {
+        assert_valid(&SECT283K1);
+    }
+
+    #[test]
+    fn test_sect283k1_known_answer() {
+        assert_known_answer(&KnownAnswer {
+            curve: &SECT283K1,
+            key: "0401110942BAC34AF8BE838A2AE03E79C84B64752229A137CD0921536081778A01B3BD87C2019A6CFA48D6EBD89E4E5CFA997EF5929071B4E1ADABA6CC01A3EAB6EDDDB36F4F507A09",
+            signature: "00CF78C9126A465D5AC2ECC587CC861E7228542EFB8588E51AEA1E4612EC5F06C3A9DE8A008F90225AC748C649A4C91AC4C0FCB5ECFB582D99C647C72C53A317CCFD890F6AE359F2",
+            authority: "04077D1DD45E4C97842C58CAD0522183C80D3B22E3253C3BE670E03F3B016DEFA7B9F2C1A206284E7421BF693AEC159C12DF30AC6B8D4DEA37E481AA83D788FB9CA2D6BE000CBED46D",
+            reconstruction_data: "0303CB1061181D3592E8262FCB8414864333735C0C689C8C69B18041CE469C848638D73252",
+            signer: "04076A01DF5A9C3FEDF62E7ADD63F2BF99EB200134FB143488EEE64D615E8C55F84ABB1912024D4E947E7244E879FD802137F7D56CC52F9F639E13DA86CEE87E0A3CD7D132E9763F22",
+            signer_signature: "000433E8D764FF53DF146A383DB10C74C1B9F682E4776C1D92BF8ACE559924B38CE6D04B013241A95C7912FEE1699EA21F4DE4B5E32BBE444858F3629E71B288AC92B56C8F7DC5CB",
+        });
+    }
+
+    #[test]
+    fn test_point_compression() {
+        let curve = &*SECT163K1;
+        let mut compressed = vec![0x02];
+        compressed.extend(curve.generator.x.to_bytes_be());
+        compressed[0] |= u8::from(
+            curve
+                .multiply_elements(
+                    &curve.generator.y,
+                    &curve.invert(&curve.generator.x).unwrap(),
+                )
+                .bit(0),
+        );
+        assert_eq!(
+            curve.decode_point(&compressed),
+            Some(curve.generator.clone())
+        );
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let curve = &*SECT283K1;
+        let private_key = BigUint::from(0x1234_5678_9ABC_DEF0_u64);
+        let public_key = curve.decode_point(&curve.public_key(&private_key)).unwrap();
+        let hash = [0x5A; 16];
+        let signature = curve.sign(&private_key, &BigUint::from(0x0FED_CBA9_u32), &hash);
+        let (r, s) = signature.split_at(curve.scalar_size());
+        assert!(curve.verify(&public_key, &hash, r, s));
+        assert!(!curve.verify(&public_key, &[0xA5; 16], r, s));
+    }
+}
@@ -0,0 +1,36 @@
+use std::fmt::Display;
+
+/// Errors that can occur when verifying the signature of a firmware image.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SignatureError {
+    /// The public key file could not be read.
+    KeyFile(std::io::ErrorKind),
+    /// The public key could not be parsed.
+    InvalidKey,
+    /// The firmware image is not signed.
+    Unsigned,
+    /// The firmware image format does not support signatures.
+    Unsupported,
+    /// The public key's curve does not match the curve of the signature.
+    CurveMismatch,
+    /// The signer's public key could not be reconstructed from its certificate.
+    InvalidCertificate,
+    /// The signature does not match the firmware image.
+    Mismatch,
+}
+
+impl Display for SignatureError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::KeyFile(kind) => write!(f, "Failed to read public key file: {kind}"),
+            Self::InvalidKey => write!(f, "Invalid public key"),
+            Self::Unsigned => write!(f, "Firmware image is not signed"),
+            Self::Unsupported => write!(f, "Firmware image format does not support signatures"),
+            Self::CurveMismatch => write!(f, "Public key does not match the signature's curve"),
+            Self::InvalidCertificate => write!(f, "Invalid signing certificate"),
+            Self::Mismatch => write!(f, "Signature verification failed"),
+        }
+    }
+}
+
+impl std::error::Error for SignatureError {}
@@ -0,0 +1,184 @@
+use std::fs::read;
+use std::path::Path;
+
+use p256::ecdsa::VerifyingKey;
+use p256::ecdsa::signature::Verifier;
+use p256::pkcs8::DecodePublicKey;
+
+use super::SignatureError;
+use super::binary_curve::{Curve, Point, SECT163K1, SECT283K1};
+
+const PEM_PREFIX: &[u8] = b"-----BEGIN";
+
+/// A public key to verify firmware image signatures against.
+///
+/// GBL images are signed using ECDSA-P256.
+/// Zigbee OTA files are signed using the sect163k1 or sect283k1 curves.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PublicKey(Key);
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Key {
+    P256(VerifyingKey),
+    Sect163k1(Point),
+    Sect283k1(Point),
+}
+
+impl PublicKey {
+    /// Parse a public key.
+    ///
+    /// ECDSA-P256 keys may be given as PEM or DER encoded `SubjectPublicKeyInfo` or as SEC 1 encoded point.
+    /// Keys on the sect163k1 or sect283k1 curves are expected as compressed or uncompressed SEC 1 encoded point.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SignatureError::InvalidKey`] if the key cannot be parsed.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SignatureError> {
+        if bytes.starts_with(PEM_PREFIX) {
+            return str::from_utf8(bytes)
+                .ok()
+                .and_then(|pem| VerifyingKey::from_public_key_pem(pem).ok())
+                .map(Self::from)
+                .ok_or(SignatureError::InvalidKey);
+        }
+
+        if is_point_size(&SECT163K1, bytes.len()) {
+            return SECT163K1
+                .decode_point(bytes)
+                .map(|point| Self(Key::Sect163k1(point)))
+                .ok_or(SignatureError::InvalidKey);
+        }
+
+        if is_point_size(&SECT283K1, bytes.len()) {
+            return SECT283K1
+                .decode_point(bytes)
+                .map(|point| Self(Key::Sect283k1(point)))
+                .ok_or(SignatureError::InvalidKey);
+        }
+
+        VerifyingKey::from_sec1_bytes(bytes)
+            .or_else(|_| VerifyingKey::from_public_key_der(bytes))
+            .map(Self::from)
+            .map_err(|_| SignatureError::InvalidKey)
+    }
+
+    /// Read and parse a public key from the given file.
+    ///
+    /// See [`PublicKey::from_bytes`] for the supported formats.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SignatureError::KeyFile`] if the file cannot be read
+    /// and [`SignatureError::InvalidKey`] if the key cannot be parsed.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, SignatureError> {
+        Self::from_bytes(&read(path).map_err(|error| SignatureError::KeyFile(error.kind()))?)
+    }
+
+    /// Verify an ECDSA-P256 signature over the SHA-256 hash of the message.
+    pub(crate) fn verify_p256(
+        &self,
+        message: &[u8],
+        r: &[u8; 32],
+        s: &[u8; 32],
+    ) -> Result<(), SignatureError> {
+        let Key::P256(key) = &self.0 else {
+            return Err(SignatureError::CurveMismatch);
+        };
+
+        let signature =
+            p256::ecdsa::Signature::from_scalars(*r, *s).map_err(|_| SignatureError::Mismatch)?;
+        key.verify(message, &signature)
+            .map_err(|_| SignatureError::Mismatch)
+    }
+
+    /// Verify a Zigbee ECDSA signature `r || s` over the given AES-MMO hash.
+    ///
+    /// If a certificate is given, this key is the certificate authority's key,
+    /// from which the signer's key is reconstructed.
+    pub(crate) fn verify_zigbee(
+        &self,
+        crypto_suite: CryptoSuite,
+        hash: &[u8],
+        signature: &[u8],
+        certificate: Option<(&[u8], &[u8])>,
+    ) -> Result<(), SignatureError> {
+        let (curve, key) = match (crypto_suite, &self.0) {
+            (CryptoSuite::Sect163k1, Key::Sect163k1(key)) => (&*SECT163K1, key),
+            (CryptoSuite::Sect283k1, Key::Sect283k1(key)) => (&*SECT283K1, key),
+            _ => return Err(SignatureError::CurveMismatch),
+        };
+
+        let reconstructed;
+        let key = if let Some((reconstruction_data, certificate_hash)) = certificate {
+            reconstructed = curve
+                .reconstruct(reconstruction_data, certificate_hash, key)
+                .ok_or(SignatureError::InvalidCertificate)?;
+            &reconstructed
+        } else {
+            key
+        };
+
+        if signature.len() != 2 * curve.scalar_size() {
+            return Err(SignatureError::Mismatch);
+        }
+
+        let (r, s) = signature.split_at(curve.scalar_size());
+
+        if curve.verify(key, hash, r, s) {
+            Ok(())
+        } else {
+            Err(SignatureError::Mismatch)
+        }
+    }
+}
+
+impl From<VerifyingKey> for PublicKey {
+    fn from(key: VerifyingKey) -> Self {
+        Self(Key::P256(key))
+    }
+}
+
+/// The Zigbee Smart Energy crypto suites.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum CryptoSuite {
+    /// Crypto suite 1 using the sect163k1 curve.
+    Sect163k1,
+    /// Crypto suite 2 using the sect283k1 curve.
+    Sect283k1,
+}
+
+/// Returns whether the size matches a compressed or uncompressed point on the curve.
+fn is_point_size(curve: &Curve, size: usize) -> bool {
+    size == 1 + curve.element_size() || size == 1 + 2 * curve.element_size()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CryptoSuite, PublicKey};
+    use crate::signature::SignatureError;
+    use crate::signature::tests::sign_sect163k1;
+
+    const HASH: [u8; 16] = [0x5A; 16];
+
+    #[test]
+    fn test_verify_zigbee() {
+        let (public_key, signature) = sign_sect163k1(&HASH);
+        let public_key = PublicKey::from_bytes(&public_key).unwrap();
+        assert_eq!(
+            public_key.verify_zigbee(CryptoSuite::Sect163k1, &HASH, &signature, None),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_verify_zigbee_signature_size() {
+        let (public_key, signature) = sign_sect163k1(&HASH);
+        let public_key = PublicKey::from_bytes(&public_key).unwrap();
+        let (r, s) = signature.split_at(signature.len() / 2);
+        let padded = [r, &[0x00], s].concat();
+        assert_eq!(
+            public_key.verify_zigbee(CryptoSuite::Sect163k1, &HASH, &padded, None),
+            Err(SignatureError::Mismatch)
+        );
+    }
+}
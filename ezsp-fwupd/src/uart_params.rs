@@ -1,3 +1,6 @@
+const DEFAULT_CHANNEL_SIZE: usize = 8;
+const DEFAULT_PROTOCOL_VERSION: u8 = 8;
+
 /// Parameters for UART communication with the EZSP device.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct UartParams {
@@ -39,3 +42,13 @@ impl UartParams {
         self.protocol_version
     }
 }
+
+impl Default for UartParams {
+    fn default() -> Self {
+        Self::new(
+            DEFAULT_CHANNEL_SIZE,
+            DEFAULT_CHANNEL_SIZE,
+            DEFAULT_PROTOCOL_VERSION,
+        )
+    }
+}
@@ -1,9 +1,22 @@
 //! XMODEM protocol implementation for EZSP firmware updates.
 
+#[cfg(feature = "clap")]
+pub use args::XmodemArgs;
+pub use block_size::BlockSize;
+pub use checksum::Checksum;
+pub use config::XmodemConfig;
+pub use error::TransferError;
 pub use frame_count::FrameCount;
 pub use send::Send;
 
+#[cfg(feature = "clap")]
+mod args;
+mod block_size;
+mod checksum;
+mod config;
+mod error;
 mod frame;
 mod frame_count;
 mod frames;
+mod response;
 mod send;
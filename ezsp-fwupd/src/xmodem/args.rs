@@ -0,0 +1,64 @@
+use std::time::Duration;
+
+use clap::Args;
+
+use super::{BlockSize, XmodemConfig};
+
+const DEFAULT: XmodemConfig = XmodemConfig::new();
+
+/// Command line arguments to tune the XMODEM transfer.
+///
+/// The defaults are taken from [`XmodemConfig::new`].
+#[derive(Debug, Args)]
+pub struct XmodemArgs {
+    #[clap(
+        long = "xmodem-1k",
+        help = "use 1024 byte XMODEM blocks, falling back to 128 byte blocks if refused"
+    )]
+    one_k: bool,
+    #[clap(long = "xmodem-retries", help = "maximum amount of XMODEM retries per frame", default_value_t = DEFAULT.retries())]
+    retries: usize,
+    #[clap(long = "xmodem-handshake-timeout", help = "time in milliseconds to wait for the bootloader to request the transfer", default_value_t = millis(DEFAULT.handshake_timeout()))]
+    handshake_timeout: u64,
+    #[clap(
+        long = "xmodem-ack-timeout",
+        help = "time in milliseconds to wait for the bootloader to acknowledge a frame [default: serial port timeout]"
+    )]
+    ack_timeout: Option<u64>,
+    #[clap(long = "xmodem-eot-timeout", help = "time in milliseconds to wait for the bootloader to acknowledge the end of the transfer", default_value_t = millis(DEFAULT.eot_timeout()))]
+    eot_timeout: u64,
+    #[clap(
+        long = "xmodem-frame-delay",
+        help = "delay in milliseconds between two consecutive XMODEM frames"
+    )]
+    frame_delay: Option<u64>,
+}
+
+impl XmodemArgs {
+    /// Return the XMODEM configuration.
+    #[must_use]
+    pub const fn config(&self) -> XmodemConfig {
+        DEFAULT
+            .with_block_size(if self.one_k {
+                BlockSize::OneK
+            } else {
+                BlockSize::Standard
+            })
+            .with_retries(self.retries)
+            .with_handshake_timeout(Duration::from_millis(self.handshake_timeout))
+            .with_ack_timeout(match self.ack_timeout {
+                Some(timeout) => Some(Duration::from_millis(timeout)),
+                None => None,
+            })
+            .with_eot_timeout(Duration::from_millis(self.eot_timeout))
+            .with_frame_delay(match self.frame_delay {
+                Some(delay) => Some(Duration::from_millis(delay)),
+                None => None,
+            })
+    }
+}
+
+/// Return the duration in whole milliseconds.
+fn millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
@@ -0,0 +1,31 @@
+use super::frame::{SOH, STX};
+
+/// Block sizes of XMODEM frames.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum BlockSize {
+    /// Standard XMODEM frames with 128 byte blocks.
+    #[default]
+    Standard,
+    /// XMODEM-1K frames with 1024 byte blocks.
+    OneK,
+}
+
+impl BlockSize {
+    /// Return the amount of payload bytes per frame.
+    #[must_use]
+    pub const fn size(self) -> usize {
+        match self {
+            Self::Standard => 128,
+            Self::OneK => 1024,
+        }
+    }
+
+    /// Return the header byte that starts a frame of this block size.
+    #[must_use]
+    pub(crate) const fn start(self) -> u8 {
+        match self {
+            Self::Standard => SOH,
+            Self::OneK => STX,
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use crc::{CRC_16_XMODEM, Crc};
+
+const CRC: Crc<u16> = Crc::<u16>::new(&CRC_16_XMODEM);
+
+/// Checksum modes of XMODEM frames.
+///
+/// The mode is chosen by the receiver's start request.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Checksum {
+    /// 16-bit CRC, requested by a `C`.
+    #[default]
+    Crc16,
+    /// Classic 8-bit additive checksum, requested by a NAK.
+    Additive,
+}
+
+impl Checksum {
+    /// Append the checksum of the given data to the given bytes.
+    pub fn append(self, data: &[u8], bytes: &mut Vec<u8>) {
+        match self {
+            Self::Crc16 => bytes.extend_from_slice(&CRC.checksum(data).to_be_bytes()),
+            Self::Additive => bytes.push(
+                data.iter()
+                    .fold(0u8, |checksum, &byte| checksum.wrapping_add(byte)),
+            ),
+        }
+    }
+
+    /// Return the size of the checksum in bytes.
+    pub const fn size(self) -> usize {
+        match self {
+            Self::Crc16 => 2,
+            Self::Additive => 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Checksum;
+
+    const TEST_DATA: &[u8] = &[
+        0xEB, 0x17, 0xA6, 0x03, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00,
+        0x00, 0xF4, 0x0A, 0x0A, 0xF4, 0x1C, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFD, 0x03, 0x03, 0xFD, 0xB0, 0x00, 0x00, 0x00,
+        0x00, 0x40, 0x00, 0x00, 0xA0, 0x39, 0x00, 0x20, 0xF1, 0x97, 0x02, 0x00, 0x91, 0xA4, 0x00,
+        0x00, 0x95, 0xA4, 0x00, 0x00, 0xA7, 0x0A, 0x0A, 0x01, 0x00, 0x42, 0x00, 0x00, 0x04, 0x18,
+        0x0F, 0xAC, 0x20, 0x69, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x14, 0x86, 0x03, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn test_crc() {
+        let mut bytes = Vec::new();
+        Checksum::Crc16.append(TEST_DATA, &mut bytes);
+        assert_eq!(bytes, 0xAAEE_u16.to_be_bytes());
+    }
+
+    #[test]
+    fn test_additive() {
+        let mut bytes = Vec::new();
+        Checksum::Additive.append(&[0x80, 0x90, 0x01], &mut bytes);
+        assert_eq!(bytes, [0x11]);
+    }
+}
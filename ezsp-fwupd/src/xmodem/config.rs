@@ -0,0 +1,119 @@
+use std::time::Duration;
+
+use super::BlockSize;
+
+const DEFAULT_RETRIES: usize = 10;
+const DEFAULT_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_EOT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Configuration of an XMODEM transfer.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct XmodemConfig {
+    block_size: BlockSize,
+    retries: usize,
+    handshake_timeout: Duration,
+    ack_timeout: Option<Duration>,
+    eot_timeout: Duration,
+    frame_delay: Option<Duration>,
+}
+
+impl XmodemConfig {
+    /// Create a new configuration with default values.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            block_size: BlockSize::Standard,
+            retries: DEFAULT_RETRIES,
+            handshake_timeout: DEFAULT_HANDSHAKE_TIMEOUT,
+            ack_timeout: None,
+            eot_timeout: DEFAULT_EOT_TIMEOUT,
+            frame_delay: None,
+        }
+    }
+
+    /// Set the block size of the frames.
+    #[must_use]
+    pub const fn with_block_size(mut self, block_size: BlockSize) -> Self {
+        self.block_size = block_size;
+        self
+    }
+
+    /// Set the maximum amount of retries per frame.
+    #[must_use]
+    pub const fn with_retries(mut self, retries: usize) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Set the time to wait for the receiver's start request.
+    #[must_use]
+    pub const fn with_handshake_timeout(mut self, timeout: Duration) -> Self {
+        self.handshake_timeout = timeout;
+        self
+    }
+
+    /// Set the time to wait for the receiver's response to a frame.
+    ///
+    /// If `None`, the serial port's timeout is used.
+    #[must_use]
+    pub const fn with_ack_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.ack_timeout = timeout;
+        self
+    }
+
+    /// Set the time to wait for the receiver to acknowledge the end of the transfer.
+    #[must_use]
+    pub const fn with_eot_timeout(mut self, timeout: Duration) -> Self {
+        self.eot_timeout = timeout;
+        self
+    }
+
+    /// Set the delay between two consecutive frames.
+    #[must_use]
+    pub const fn with_frame_delay(mut self, delay: Option<Duration>) -> Self {
+        self.frame_delay = delay;
+        self
+    }
+
+    /// Return the block size of the frames.
+    #[must_use]
+    pub const fn block_size(&self) -> BlockSize {
+        self.block_size
+    }
+
+    /// Return the maximum amount of retries per frame.
+    #[must_use]
+    pub const fn retries(&self) -> usize {
+        self.retries
+    }
+
+    /// Return the time to wait for the receiver's start request.
+    #[must_use]
+    pub const fn handshake_timeout(&self) -> Duration {
+        self.handshake_timeout
+    }
+
+    /// Return the time to wait for the receiver's response to a frame, if set.
+    #[must_use]
+    pub const fn ack_timeout(&self) -> Option<Duration> {
+        self.ack_timeout
+    }
+
+    /// Return the time to wait for the receiver to acknowledge the end of the transfer.
+    #[must_use]
+    pub const fn eot_timeout(&self) -> Duration {
+        self.eot_timeout
+    }
+
+    /// Return the delay between two consecutive frames, if set.
+    #[must_use]
+    pub const fn frame_delay(&self) -> Option<Duration> {
+        self.frame_delay
+    }
+}
+
+impl Default for XmodemConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
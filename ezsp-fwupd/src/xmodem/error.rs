@@ -0,0 +1,47 @@
+use std::fmt::Display;
+use std::io::ErrorKind;
+
+/// Errors of an XMODEM transfer that are reported to the caller.
+///
+/// These are wrapped in an [`std::io::Error`] and can be retrieved via
+/// [`get_ref()`](std::io::Error::get_ref) and [`downcast_ref()`](std::error::Error::downcast_ref).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum TransferError {
+    /// The receiver cancelled the transfer by sending two consecutive CAN bytes.
+    Cancelled,
+    /// The receiver sent an unexpected response to a frame.
+    UnexpectedResponse(u8),
+}
+
+impl TransferError {
+    /// Return whether the given I/O error is this transfer error.
+    #[must_use]
+    pub fn matches(self, error: &std::io::Error) -> bool {
+        error
+            .get_ref()
+            .and_then(|error| error.downcast_ref::<Self>())
+            == Some(&self)
+    }
+}
+
+impl Display for TransferError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Cancelled => write!(f, "Transfer cancelled by receiver"),
+            Self::UnexpectedResponse(byte) => {
+                write!(f, "Received unexpected response: {byte:#04X}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TransferError {}
+
+impl From<TransferError> for std::io::Error {
+    fn from(error: TransferError) -> Self {
+        match error {
+            TransferError::Cancelled => Self::new(ErrorKind::ConnectionAborted, error),
+            TransferError::UnexpectedResponse(_) => Self::new(ErrorKind::InvalidData, error),
+        }
+    }
+}
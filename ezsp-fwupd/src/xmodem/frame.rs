@@ -1,70 +1,94 @@
-use crc::{CRC_16_XMODEM, Crc};
+use super::{BlockSize, Checksum};
 
 const ONES_COMPLEMENT: u8 = 0xFF;
-const SOH: u8 = 0x01;
-const PAYLOAD_OFFSET: usize = 3;
+pub const SOH: u8 = 0x01;
+pub const STX: u8 = 0x02;
 pub const EOT: u8 = 0x04;
 pub const ACK: u8 = 0x06;
 pub const NAK: u8 = 0x15;
-pub const PAYLOAD_SIZE: usize = 128;
-pub const PACKET_SIZE: usize = PAYLOAD_SIZE + 5;
-pub type Payload = [u8; PAYLOAD_SIZE];
-pub type PacketBytes = [u8; PACKET_SIZE];
-
-const CRC: Crc<u16> = Crc::<u16>::new(&CRC_16_XMODEM);
+pub const CAN: u8 = 0x18;
+pub const CRC_REQUEST: u8 = b'C';
 
 /// Represents an Xmodem packet structure.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Frame {
-    soh: u8,
+    block_size: BlockSize,
+    checksum: Checksum,
     blk: u8,
     cmp: u8,
-    data: Payload,
-    chk: u16,
+    data: Box<[u8]>,
 }
 
 impl Frame {
     /// Creates a new Xmodem packet with the given block number and data.
-    pub const fn new(blk: u8, data: Payload) -> Self {
+    ///
+    /// The data must be exactly as long as the block size.
+    pub fn new(block_size: BlockSize, checksum: Checksum, blk: u8, data: Box<[u8]>) -> Self {
+        debug_assert_eq!(data.len(), block_size.size());
         Self {
-            soh: SOH,
+            block_size,
+            checksum,
             blk,
             cmp: blk ^ ONES_COMPLEMENT,
-            chk: CRC.checksum(&data),
             data,
         }
     }
 
+    /// Returns the block size of the packet.
+    pub const fn block_size(&self) -> BlockSize {
+        self.block_size
+    }
+
     /// Returns the bytes of the packet.
-    pub fn into_bytes(self) -> PacketBytes {
-        let mut result = [0; PACKET_SIZE];
-        result[0] = self.soh;
-        result[1] = self.blk;
-        result[2] = self.cmp;
-        result[PAYLOAD_OFFSET..PAYLOAD_SIZE + PAYLOAD_OFFSET].copy_from_slice(&self.data);
-        result[PACKET_SIZE - 2..PACKET_SIZE].copy_from_slice(&self.chk.to_be_bytes());
+    pub fn into_bytes(self) -> Vec<u8> {
+        let mut result = Vec::with_capacity(3 + self.data.len() + self.checksum.size());
+        result.push(self.block_size.start());
+        result.push(self.blk);
+        result.push(self.cmp);
+        result.extend_from_slice(&self.data);
+        self.checksum.append(&self.data, &mut result);
         result
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::CRC;
+    use super::{BlockSize, Checksum, Frame, SOH, STX};
 
-    const TEST_DATA: &[u8] = &[
-        0xEB, 0x17, 0xA6, 0x03, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00,
-        0x00, 0xF4, 0x0A, 0x0A, 0xF4, 0x1C, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00,
-        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFD, 0x03, 0x03, 0xFD, 0xB0, 0x00, 0x00, 0x00,
-        0x00, 0x40, 0x00, 0x00, 0xA0, 0x39, 0x00, 0x20, 0xF1, 0x97, 0x02, 0x00, 0x91, 0xA4, 0x00,
-        0x00, 0x95, 0xA4, 0x00, 0x00, 0xA7, 0x0A, 0x0A, 0x01, 0x00, 0x42, 0x00, 0x00, 0x04, 0x18,
-        0x0F, 0xAC, 0x20, 0x69, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x14, 0x86, 0x03, 0x00,
-        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    ];
+    #[test]
+    fn test_into_bytes() {
+        let frame = Frame::new(
+            BlockSize::Standard,
+            Checksum::Crc16,
+            1,
+            vec![0xAB; 128].into_boxed_slice(),
+        );
+        let bytes = frame.into_bytes();
+        assert_eq!(bytes.len(), 133);
+        assert_eq!(bytes[..3], [SOH, 0x01, 0xFE]);
+
+        let frame = Frame::new(
+            BlockSize::OneK,
+            Checksum::Crc16,
+            2,
+            vec![0xAB; 1024].into_boxed_slice(),
+        );
+        let bytes = frame.into_bytes();
+        assert_eq!(bytes.len(), 1029);
+        assert_eq!(bytes[..3], [STX, 0x02, 0xFD]);
+    }
 
     #[test]
-    fn test_crc() {
-        assert_eq!(CRC.checksum(TEST_DATA), 0xAAEE);
+    fn test_into_bytes_additive() {
+        let frame = Frame::new(
+            BlockSize::Standard,
+            Checksum::Additive,
+            3,
+            vec![0x01; 128].into_boxed_slice(),
+        );
+        let bytes = frame.into_bytes();
+        assert_eq!(bytes.len(), 132);
+        assert_eq!(bytes[..3], [SOH, 0x03, 0xFC]);
+        assert_eq!(bytes[131], 0x80);
     }
 }
@@ -1,16 +1,25 @@
-use super::frame::PAYLOAD_SIZE;
+use super::BlockSize;
+use crate::ota_file::UpgradeImage;
 
 /// Trait for counting the number of frames in an XMODEM transfer.
 pub trait FrameCount {
-    /// Returns the number of frames in the XMODEM transfer.
-    fn frame_count(&self) -> usize;
+    /// Returns the number of frames of the given block size in the XMODEM transfer.
+    fn frame_count(&self, block_size: BlockSize) -> usize;
 }
 
 impl<T> FrameCount for T
 where
     T: AsRef<[u8]>,
 {
-    fn frame_count(&self) -> usize {
-        self.as_ref().len().div_ceil(PAYLOAD_SIZE)
+    fn frame_count(&self, block_size: BlockSize) -> usize {
+        self.as_ref().len().div_ceil(block_size.size())
+    }
+}
+
+impl<R> FrameCount for UpgradeImage<'_, R> {
+    fn frame_count(&self, block_size: BlockSize) -> usize {
+        usize::try_from(self.size())
+            .unwrap_or(usize::MAX)
+            .div_ceil(block_size.size())
     }
 }
@@ -1,71 +1,120 @@
-use std::iter::repeat;
+use std::io::{ErrorKind, Read};
 
-use super::frame::{Frame, PAYLOAD_SIZE};
+use super::frame::Frame;
+use super::{BlockSize, Checksum};
 
 const FILLER: u8 = 0xFF;
 
-/// An iterator that produces Xmodem frames from a byte stream.
+/// An iterator that produces Xmodem frames from a reader.
 #[derive(Debug)]
 pub struct Frames<T> {
-    bytes: T,
-    buffer: Vec<u8>,
+    reader: T,
+    block_size: BlockSize,
+    checksum: Checksum,
     index: u8,
+    last: Vec<u8>,
+    pending: Vec<u8>,
 }
 
 impl<T> Frames<T> {
-    /// Creates a new `XmodemFrames` iterator from the given byte stream.
-    pub const fn new(bytes: T) -> Self {
+    /// Creates a new `XmodemFrames` iterator from the given reader.
+    pub const fn new(reader: T, block_size: BlockSize, checksum: Checksum) -> Self {
         Self {
-            bytes,
-            buffer: Vec::new(),
+            reader,
+            block_size,
+            checksum,
             index: 1,
+            last: Vec::new(),
+            pending: Vec::new(),
         }
     }
+
+    /// Falls back to standard 128 byte blocks.
+    ///
+    /// The data of the last frame is produced again, split into standard frames,
+    /// starting with the last frame's block number.
+    pub fn fall_back(&mut self) {
+        self.block_size = BlockSize::Standard;
+        self.index = self.index.wrapping_sub(1);
+
+        self.pending.splice(..0, self.last.drain(..));
+    }
 }
 
 impl<T> Iterator for Frames<T>
 where
-    T: Iterator<Item = u8>,
+    T: Read,
 {
-    type Item = Frame;
+    type Item = std::io::Result<Frame>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.buffer.clear();
-        self.buffer.extend(self.bytes.by_ref().take(PAYLOAD_SIZE));
+        let mut payload = vec![FILLER; self.block_size.size()].into_boxed_slice();
+        let mut filled = self.pending.len().min(payload.len());
+        payload[..filled].copy_from_slice(&self.pending[..filled]);
+        self.pending.drain(..filled);
 
-        if self.buffer.is_empty() {
-            return None;
+        while filled < payload.len() {
+            match self.reader.read(&mut payload[filled..]) {
+                Ok(0) => break,
+                Ok(size) => filled += size,
+                Err(error) if error.kind() == ErrorKind::Interrupted => {}
+                Err(error) => return Some(Err(error)),
+            }
         }
 
-        let mut payload = [0; PAYLOAD_SIZE];
-
-        for (dst, src) in payload
-            .iter_mut()
-            .zip(self.buffer.iter().copied().chain(repeat(FILLER)))
-        {
-            *dst = src;
+        if filled == 0 {
+            return None;
         }
 
-        let frame = Frame::new(self.index, payload);
+        self.last.clear();
+        self.last.extend_from_slice(&payload[..filled]);
+        payload[filled..].fill(FILLER);
+        let frame = Frame::new(self.block_size, self.checksum, self.index, payload);
         self.index = self.index.wrapping_add(1);
-        Some(frame)
+        Some(Ok(frame))
     }
 }
 
-impl<T> ExactSizeIterator for Frames<T>
-where
-    T: Iterator<Item = u8> + ExactSizeIterator,
-{
-    fn len(&self) -> usize {
-        self.bytes.len().div_ceil(PAYLOAD_SIZE)
+#[cfg(test)]
+mod tests {
+    use super::{BlockSize, Checksum, FILLER, Frames};
+
+    #[test]
+    fn test_frames() {
+        let data: Vec<u8> = (0..=199).collect();
+        let frames: Vec<_> = Frames::new(data.as_slice(), BlockSize::Standard, Checksum::Crc16)
+            .map(|frame| frame.unwrap().into_bytes())
+            .collect();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0][1], 1);
+        assert_eq!(frames[1][1], 2);
+        assert_eq!(frames[1][3..3 + 72], data[128..]);
+        assert!(
+            frames[1][3 + 72..3 + 128]
+                .iter()
+                .all(|&byte| byte == FILLER)
+        );
     }
-}
 
-impl<T> From<T> for Frames<T::IntoIter>
-where
-    T: IntoIterator<Item = u8>,
-{
-    fn from(bytes: T) -> Self {
-        Self::new(bytes.into_iter())
+    #[test]
+    fn test_fall_back() {
+        let data: Vec<u8> = (0..1500).map(|index: u32| index.to_le_bytes()[0]).collect();
+        let mut frames = Frames::new(data.as_slice(), BlockSize::OneK, Checksum::Crc16);
+        let first = frames.next().unwrap().unwrap();
+        assert_eq!(first.block_size(), BlockSize::OneK);
+        frames.fall_back();
+
+        let frames: Vec<_> = frames.map(|frame| frame.unwrap().into_bytes()).collect();
+        assert_eq!(frames.len(), 12);
+        assert!(frames.iter().all(|frame| frame.len() == 133));
+        assert_eq!(frames[0][1], 1);
+        assert_eq!(frames[11][1], 12);
+
+        let payload: Vec<u8> = frames
+            .iter()
+            .flat_map(|frame| frame[3..3 + 128].iter().copied())
+            .collect();
+        assert_eq!(payload[..1500], data);
+        assert!(payload[1500..].iter().all(|&byte| byte == FILLER));
     }
 }
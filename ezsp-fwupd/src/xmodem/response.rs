@@ -0,0 +1,25 @@
+use super::frame::{ACK, CAN, NAK};
+
+/// Responses of an XMODEM receiver to a frame.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Response {
+    /// The frame was received successfully.
+    Ack,
+    /// The frame was not received successfully and shall be retransmitted.
+    Nak,
+    /// A CAN byte, which cancels the transfer if followed by another one.
+    Can,
+}
+
+impl TryFrom<u8> for Response {
+    type Error = u8;
+
+    fn try_from(byte: u8) -> Result<Self, Self::Error> {
+        match byte {
+            ACK => Ok(Self::Ack),
+            NAK => Ok(Self::Nak),
+            CAN => Ok(Self::Can),
+            other => Err(other),
+        }
+    }
+}
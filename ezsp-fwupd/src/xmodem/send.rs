@@ -1,36 +1,236 @@
+use std::io::{ErrorKind, Read, Write};
+use std::thread::sleep;
+use std::time::Instant;
+
 use indicatif::ProgressBar;
-use log::debug;
+use log::{debug, info, trace, warn};
 
+use self::handshake::Handshake;
 use self::send_frame::SendFrame;
-use super::frame::EOT;
+use super::frame::{ACK, EOT, NAK};
 use super::frames::Frames;
-use crate::{FlashProgress, IgnoreTimeout};
+use super::response::Response;
+use super::{BlockSize, TransferError, XmodemConfig};
+use crate::FlashProgress;
 
+mod handshake;
+#[cfg(test)]
+mod mock;
 mod send_frame;
 
-const MAX_RETRIES: usize = 10;
-
 /// Trait for sending data using the XMODEM protocol.
-pub trait Send: SendFrame {
-    /// Sends a file using the XMODEM protocol.
-    fn send<T>(&mut self, data: T, progress_bar: Option<&ProgressBar>) -> std::io::Result<Box<[u8]>>
+pub trait Send: SendFrame + Handshake {
+    /// Sends the data read from the given reader using the XMODEM protocol.
+    ///
+    /// The transfer starts once the receiver requests it, which also determines the checksum mode.
+    /// The transfer is carried out according to the given configuration.
+    /// If XMODEM-1K is requested and the receiver NAKs the first 1K block,
+    /// the transfer falls back to standard 128 byte blocks.
+    ///
+    /// If the transfer fails, it is cancelled by sending two consecutive CAN bytes,
+    /// so that the receiver can return to its menu.
+    /// If the receiver cancels the transfer, a [`TransferError::Cancelled`] is returned.
+    fn send<T>(
+        &mut self,
+        data: T,
+        config: &XmodemConfig,
+        progress_bar: Option<&ProgressBar>,
+    ) -> std::io::Result<Box<[u8]>>
     where
-        T: IntoIterator<Item = u8>,
+        T: Read,
     {
-        debug!("Starting XMODEM file transfer...");
+        let checksum = self.await_start_request(config.handshake_timeout())?;
+        debug!("Starting XMODEM file transfer with {checksum:?} checksum...");
+        let frames = Frames::new(data, config.block_size(), checksum);
 
-        for (index, frame) in Frames::new(data.into_iter()).enumerate() {
-            self.send_frame(index, frame)?;
-            progress_bar.increase();
-        }
+        let index = match send_frames(self, frames, config, progress_bar) {
+            Ok(index) => index,
+            Err(error) => {
+                if !TransferError::Cancelled.matches(&error) {
+                    self.cancel();
+                }
 
+                return Err(error);
+            }
+        };
+
+        progress_bar.set_length(index);
         progress_bar.println("Transfer complete, sending EOT...");
-        self.write_all(&[EOT])?;
-        self.flush()?;
-        let mut buffer = Vec::new();
-        self.read_to_end(&mut buffer).ignore_timeout()?;
-        Ok(buffer.into_boxed_slice())
+        send_eot(self, config)
+    }
+}
+
+impl<T> Send for T where T: SendFrame + Handshake {}
+
+/// Sends all frames, returning the amount of frames sent.
+///
+/// If the receiver NAKs the first 1K block, the frames fall back to standard 128 byte blocks.
+fn send_frames<S, T>(
+    sender: &mut S,
+    mut frames: Frames<T>,
+    config: &XmodemConfig,
+    progress_bar: Option<&ProgressBar>,
+) -> std::io::Result<usize>
+where
+    S: SendFrame + ?Sized,
+    T: Read,
+{
+    let mut index: usize = 0;
+
+    while let Some(frame) = frames.next() {
+        let frame = frame?;
+
+        if index > 0
+            && let Some(delay) = config.frame_delay()
+        {
+            sleep(delay);
+        }
+
+        let first_one_k = index == 0 && frame.block_size() == BlockSize::OneK;
+        let bytes = frame.into_bytes();
+
+        if first_one_k {
+            match sender.try_send_frame(&bytes) {
+                Ok(Response::Ack) => {
+                    progress_bar.increase();
+                    index += 1;
+                    continue;
+                }
+                Ok(Response::Nak) => {
+                    info!("Receiver refused 1K block, falling back to 128 byte blocks...");
+                    frames.fall_back();
+                    progress_bar.scale_length(BlockSize::OneK.size() / BlockSize::Standard.size());
+                    continue;
+                }
+                Ok(Response::Can) => return Err(TransferError::Cancelled.into()),
+                Err(_) => {}
+            }
+        }
+
+        sender.send_frame(index, &bytes, config.retries())?;
+        progress_bar.increase();
+        index += 1;
+    }
+
+    Ok(index)
+}
+
+/// Sends EOT and waits for the receiver to acknowledge it.
+///
+/// The EOT is resent if the receiver NAKs it.
+/// Returns all bytes received until the ACK or the EOT timeout.
+fn send_eot<S>(sender: &mut S, config: &XmodemConfig) -> std::io::Result<Box<[u8]>>
+where
+    S: Read + Write + ?Sized,
+{
+    let deadline = Instant::now() + config.eot_timeout();
+    let mut response = Vec::new();
+    let mut naks: usize = 0;
+    let mut byte = [0];
+    sender.write_all(&[EOT])?;
+    sender.flush()?;
+
+    loop {
+        match sender.read(&mut byte) {
+            Ok(0) => break,
+            Ok(_) => {
+                response.extend_from_slice(&byte);
+
+                match byte {
+                    [ACK] => {
+                        debug!("EOT acknowledged");
+                        break;
+                    }
+                    [NAK] if naks < config.retries() => {
+                        trace!("EOT not acknowledged, resending...");
+                        naks += 1;
+                        sender.write_all(&[EOT])?;
+                        sender.flush()?;
+                    }
+                    _ => {}
+                }
+            }
+            Err(error) if matches!(error.kind(), ErrorKind::TimedOut | ErrorKind::Interrupted) => {}
+            Err(error) => return Err(error),
+        }
+
+        if Instant::now() >= deadline {
+            warn!("Receiver did not acknowledge EOT");
+            break;
+        }
     }
+
+    Ok(response.into_boxed_slice())
 }
 
-impl<T> Send for T where T: SendFrame {}
+#[cfg(test)]
+mod tests {
+    use super::Send;
+    use super::mock::Mock;
+    use crate::xmodem::frame::{ACK, CAN, CRC_REQUEST, EOT, NAK};
+    use crate::xmodem::{BlockSize, TransferError, XmodemConfig};
+
+    #[test]
+    fn test_send() {
+        let mut mock = Mock::new(&[CRC_REQUEST, ACK, NAK, ACK, ACK]);
+        mock.send(&[0x00; 200][..], &XmodemConfig::new(), None)
+            .unwrap();
+        assert_eq!(mock.written.len(), 3 * 133 + 1);
+        assert_eq!(mock.written.last(), Some(&EOT));
+    }
+
+    #[test]
+    fn test_fall_back() {
+        let mut mock = Mock::new(&[CRC_REQUEST, NAK, ACK, ACK]);
+        mock.send(
+            &[0x00; 200][..],
+            &XmodemConfig::new().with_block_size(BlockSize::OneK),
+            None,
+        )
+        .unwrap();
+        assert_eq!(mock.written.len(), 1029 + 2 * 133 + 1);
+    }
+
+    #[test]
+    fn test_cancel_after_max_retries() {
+        let mut responses = vec![CRC_REQUEST];
+        responses.extend([NAK; 11]);
+        let mut mock = Mock::new(&responses);
+        assert!(
+            mock.send(&[0x00; 100][..], &XmodemConfig::new(), None)
+                .is_err()
+        );
+        assert_eq!(mock.written.len(), 11 * 133 + 2);
+        assert!(mock.written.ends_with(&[CAN, CAN]));
+    }
+
+    #[test]
+    fn test_cancelled_by_receiver() {
+        let mut mock = Mock::new(&[CRC_REQUEST, ACK, CAN, CAN]);
+        let error = mock
+            .send(&[0x00; 200][..], &XmodemConfig::new(), None)
+            .unwrap_err();
+        assert!(TransferError::Cancelled.matches(&error));
+        assert_eq!(mock.written.len(), 2 * 133);
+    }
+
+    #[test]
+    fn test_retries() {
+        let mut mock = Mock::new(&[CRC_REQUEST, NAK, NAK, NAK]);
+        let config = XmodemConfig::new().with_retries(2);
+        assert!(mock.send(&[0x00; 100][..], &config, None).is_err());
+        assert_eq!(mock.written.len(), 3 * 133 + 2);
+    }
+
+    #[test]
+    fn test_eot_nak() {
+        let mut mock = Mock::new(&[CRC_REQUEST, ACK, NAK, ACK, b'\r']);
+        let response = mock
+            .send(&[0x00; 100][..], &XmodemConfig::new(), None)
+            .unwrap();
+        assert_eq!(mock.written.len(), 133 + 2);
+        assert!(mock.written.ends_with(&[EOT, EOT]));
+        assert_eq!(*response, [NAK, ACK]);
+        assert_eq!(mock.responses, [b'\r']);
+    }
+}
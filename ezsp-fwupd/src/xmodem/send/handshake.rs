@@ -0,0 +1,86 @@
+use std::io::{ErrorKind, Read};
+use std::time::{Duration, Instant};
+
+use log::{debug, trace};
+
+use crate::xmodem::Checksum;
+use crate::xmodem::frame::{CRC_REQUEST, NAK};
+
+/// Sealed trait for awaiting the receiver's start request.
+pub trait Handshake: Read {
+    /// Waits for the receiver to request the start of the transfer.
+    ///
+    /// A `C` requests frames with a 16-bit CRC, a NAK requests frames with an 8-bit additive checksum.
+    /// Any other bytes are discarded.
+    /// The timeout is only checked between reads, so the reader's own timeout must not exceed it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`std::io::Error`] if no start request is received within the given timeout
+    /// or if an I/O error occurs.
+    fn await_start_request(&mut self, timeout: Duration) -> std::io::Result<Checksum> {
+        debug!("Waiting for start request...");
+        let deadline = Instant::now() + timeout;
+        let mut byte = [0];
+
+        loop {
+            match self.read(&mut byte) {
+                Ok(0) => return Err(ErrorKind::UnexpectedEof.into()),
+                Ok(_) => match byte {
+                    [CRC_REQUEST] => return Ok(Checksum::Crc16),
+                    [NAK] => return Ok(Checksum::Additive),
+                    [other] => trace!("Discarding {other:#04X}"),
+                },
+                Err(error)
+                    if matches!(error.kind(), ErrorKind::TimedOut | ErrorKind::Interrupted) => {}
+                Err(error) => return Err(error),
+            }
+
+            if Instant::now() >= deadline {
+                return Err(std::io::Error::new(
+                    ErrorKind::TimedOut,
+                    "Receiver did not request the start of the transfer",
+                ));
+            }
+        }
+    }
+}
+
+impl<T> Handshake for T where T: Read {}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::Handshake;
+    use crate::xmodem::Checksum;
+    use crate::xmodem::frame::NAK;
+
+    const TIMEOUT: Duration = Duration::from_secs(1);
+
+    #[test]
+    fn test_crc_request() {
+        let mut receiver = b"\r\nbegin upload\r\nC".as_slice();
+        assert_eq!(
+            receiver.await_start_request(TIMEOUT).unwrap(),
+            Checksum::Crc16
+        );
+        assert!(receiver.is_empty());
+    }
+
+    #[test]
+    fn test_nak_request() {
+        let mut receiver = [0x00, NAK, b'C'].as_slice();
+        assert_eq!(
+            receiver.await_start_request(TIMEOUT).unwrap(),
+            Checksum::Additive
+        );
+        assert_eq!(receiver, b"C");
+    }
+
+    #[test]
+    fn test_no_request() {
+        let mut receiver = b"\r\n".as_slice();
+        assert!(receiver.await_start_request(TIMEOUT).is_err());
+    }
+}
@@ -0,0 +1,33 @@
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+
+/// A serial port mock that replays canned responses.
+pub struct Mock {
+    pub responses: VecDeque<u8>,
+    pub written: Vec<u8>,
+}
+
+impl Mock {
+    pub fn new(responses: &[u8]) -> Self {
+        Self {
+            responses: responses.iter().copied().collect(),
+            written: Vec::new(),
+        }
+    }
+}
+
+impl Read for Mock {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.responses.read(buf)
+    }
+}
+
+impl Write for Mock {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.written.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
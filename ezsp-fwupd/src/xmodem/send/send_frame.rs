@@ -1,48 +1,56 @@
 use std::io::{Read, Write};
 
-use log::{debug, error, trace};
+use log::{debug, error, trace, warn};
 
-use crate::ignore_timeout::IgnoreTimeout;
-use crate::xmodem::frame::{ACK, Frame, NAK};
-use crate::xmodem::send::MAX_RETRIES;
+use crate::ClearBuffer;
+use crate::xmodem::TransferError;
+use crate::xmodem::frame::CAN;
+use crate::xmodem::response::Response;
+
+const CANCEL: [u8; 2] = [CAN, CAN];
 
 /// Sealed trait for sending XMODEM frames.
 pub trait SendFrame: Read + Write {
-    /// Sends a single frame with retries.
-    fn send_frame(&mut self, index: usize, frame: Frame) -> std::io::Result<()> {
+    /// Sends a single frame with up to the given amount of retries.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`std::io::Error`] if the receiver cancels the transfer
+    /// or if the frame could not be sent within the maximum amount of retries.
+    fn send_frame(&mut self, index: usize, frame: &[u8], retries: usize) -> std::io::Result<()> {
         debug!("Sending frame #{index}...");
-
+        trace!("Sending frame #{index}: {frame:#04X?}");
         let mut ctr: usize = 0;
-        let bytes = frame.into_bytes();
-        trace!("Sending frame #{index}: {bytes:#04X?}");
 
         loop {
-            match self.try_send_frame(&bytes) {
-                Ok(()) => return Ok(()),
-                Err(error) => {
-                    if ctr >= MAX_RETRIES {
-                        error!("max retries exceeded for frame #{index}");
-                        return Err(error);
-                    }
-
-                    debug!("Attempt {ctr} failed: {error}, retrying...");
-                }
+            let error = match self.try_send_frame(frame) {
+                Ok(Response::Ack) => return Ok(()),
+                Ok(Response::Nak) => std::io::Error::other("NAK received, retransmitting frame"),
+                Ok(Response::Can) => return Err(TransferError::Cancelled.into()),
+                Err(error) => error,
+            };
+
+            if ctr >= retries {
+                error!("max retries exceeded for frame #{index}");
+                return Err(error);
             }
 
+            debug!("Attempt {ctr} failed: {error}, retrying...");
             ctr += 1;
         }
     }
 
-    /// Attempts to send a frame and waits for an acknowledgment.
+    /// Attempts to send a frame and waits for the receiver's response.
     ///
-    /// # Returns
-    ///
-    /// Returns `Ok(())` on success, or an error if a NAK or unexpected response is received.
+    /// A single ACK or NAK byte is the complete response.
+    /// A CAN byte is only accepted as a response if it is followed by a second one,
+    /// in which case the receiver cancelled the transfer.
+    /// Any stray bytes are only drained if the response was unexpected.
     ///
     /// # Errors
     ///
-    /// If a NAK is received, it returns an error prompting a retransmission.
-    fn try_send_frame(&mut self, frame: &[u8]) -> std::io::Result<()> {
+    /// Returns an [`std::io::Error`] if an I/O error occurs or an unexpected response is received.
+    fn try_send_frame(&mut self, frame: &[u8]) -> std::io::Result<Response> {
         self.write_all(frame)?;
         self.flush()?;
 
@@ -50,18 +58,79 @@ pub trait SendFrame: Read + Write {
         self.read_exact(&mut response)?;
         trace!("Received {response:#02X?}");
         let [byte] = response;
-        let mut excess = Vec::new();
-        self.read_to_end(&mut excess).ignore_timeout()?;
-        trace!("Received {} excess bytes: {excess:#04X?}", excess.len());
-
-        match byte {
-            ACK => Ok(()),
-            NAK => Err(std::io::Error::other("NAK received, retransmitting frame")),
-            other => Err(std::io::Error::other(format!(
-                "Received unexpected response: {other}"
-            ))),
+
+        match Response::try_from(byte) {
+            Ok(Response::Can) => {
+                self.read_exact(&mut response)?;
+                trace!("Received {response:#02X?}");
+
+                if response == [CAN] {
+                    Ok(Response::Can)
+                } else {
+                    self.clear_buffer()?;
+                    Err(TransferError::UnexpectedResponse(CAN).into())
+                }
+            }
+            Ok(response) => Ok(response),
+            Err(other) => {
+                self.clear_buffer()?;
+                Err(TransferError::UnexpectedResponse(other).into())
+            }
+        }
+    }
+
+    /// Cancels the transfer by sending two consecutive CAN bytes.
+    ///
+    /// Failures are logged, since the transfer is being aborted anyway.
+    fn cancel(&mut self) {
+        warn!("Cancelling transfer...");
+
+        if let Err(error) = self.write_all(&CANCEL).and_then(|()| self.flush()) {
+            error!("Failed to cancel transfer: {error}");
         }
     }
 }
 
 impl<T> SendFrame for T where T: Read + Write {}
+
+#[cfg(test)]
+mod tests {
+    use super::SendFrame;
+    use crate::xmodem::TransferError;
+    use crate::xmodem::frame::{ACK, CAN, NAK};
+    use crate::xmodem::response::Response;
+    use crate::xmodem::send::mock::Mock;
+
+    #[test]
+    fn test_ack_is_complete_response() {
+        let mut mock = Mock::new(&[ACK, NAK, ACK]);
+        assert_eq!(mock.try_send_frame(&[0x01]).unwrap(), Response::Ack);
+        assert_eq!(mock.try_send_frame(&[0x02]).unwrap(), Response::Nak);
+        assert_eq!(mock.try_send_frame(&[0x02]).unwrap(), Response::Ack);
+        assert_eq!(mock.written, [0x01, 0x02, 0x02]);
+        assert!(mock.responses.is_empty());
+    }
+
+    #[test]
+    fn test_unexpected_response_drains_buffer() {
+        let mut mock = Mock::new(&[0x43, 0x43, 0x43]);
+        let error = mock.try_send_frame(&[0x01]).unwrap_err();
+        assert!(TransferError::UnexpectedResponse(0x43).matches(&error));
+        assert!(mock.responses.is_empty());
+    }
+
+    #[test]
+    fn test_single_can_is_unexpected() {
+        let mut mock = Mock::new(&[CAN, ACK]);
+        let error = mock.try_send_frame(&[0x01]).unwrap_err();
+        assert!(TransferError::UnexpectedResponse(CAN).matches(&error));
+    }
+
+    #[test]
+    fn test_cancelled_by_receiver() {
+        let mut mock = Mock::new(&[CAN, CAN, ACK]);
+        let error = mock.send_frame(0, &[0x01], 10).unwrap_err();
+        assert!(TransferError::Cancelled.matches(&error));
+        assert_eq!(mock.written, [0x01]);
+    }
+}
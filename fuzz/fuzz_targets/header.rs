@@ -0,0 +1,9 @@
+#![no_main]
+
+use ezsp_fwupd::ota_file::Header;
+use le_stream::FromLeStream;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = Header::from_le_stream(data.iter().copied());
+});
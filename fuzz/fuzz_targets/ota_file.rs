@@ -0,0 +1,10 @@
+#![no_main]
+
+use ezsp_fwupd::OtaFile;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(ota_file) = OtaFile::try_from(data) {
+        let _ = ota_file.validate();
+    }
+});
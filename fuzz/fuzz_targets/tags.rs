@@ -0,0 +1,12 @@
+#![no_main]
+
+use ezsp_fwupd::ota_file::Tags;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let size = u32::try_from(data.len()).unwrap_or(u32::MAX);
+
+    for tag in Tags::new(data.iter().copied(), size).flatten() {
+        let _ = tag.kind();
+    }
+});